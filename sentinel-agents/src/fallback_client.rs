@@ -0,0 +1,187 @@
+// sentinel-agents/src/fallback_client.rs
+// ==============================================================================
+// OMEGA PLATFORM - SENTINEL AGENT FALLBACK MESH CLIENT
+// ==============================================================================
+//
+// `mesh_client`'s worker previously targeted a single mesh address, so any
+// single-endpoint outage dropped telemetry. `FallbackMeshClient` holds an
+// ordered list of endpoints, each with a lightweight health score, and
+// transparently fails over: a failed send or health probe cools the
+// endpoint down and rotates to the next healthy one, while recovered
+// endpoints are periodically re-promoted back to primary.
+//
+
+use std::time::{Duration, Instant};
+
+use tonic::transport::Channel;
+
+use crate::mesh_client::{connect_to_mesh, TelemetryData};
+
+/// How long a failed endpoint is skipped before it's eligible again.
+const COOLDOWN: Duration = Duration::from_secs(30);
+/// Score nudge on a successful send/probe, and the penalty on failure.
+const SCORE_SUCCESS_DELTA: i32 = 1;
+const SCORE_FAILURE_PENALTY: i32 = 50;
+const SCORE_MAX: i32 = 100;
+
+struct Endpoint {
+    addr: String,
+    channel: Option<Channel>,
+    score: i32,
+    cooldown_until: Option<Instant>,
+}
+
+impl Endpoint {
+    fn new(addr: String) -> Self {
+        Endpoint {
+            addr,
+            channel: None,
+            score: SCORE_MAX,
+            cooldown_until: None,
+        }
+    }
+
+    /// Re-promotes an endpoint whose cooldown has elapsed.
+    fn refresh_cooldown(&mut self) {
+        if let Some(until) = self.cooldown_until {
+            if Instant::now() >= until {
+                self.cooldown_until = None;
+            }
+        }
+    }
+
+    fn is_reachable(&self) -> bool {
+        self.cooldown_until.is_none()
+    }
+
+    fn record_success(&mut self) {
+        self.score = (self.score + SCORE_SUCCESS_DELTA).min(SCORE_MAX);
+        self.cooldown_until = None;
+    }
+
+    fn record_failure(&mut self) {
+        self.score -= SCORE_FAILURE_PENALTY;
+        self.cooldown_until = Some(Instant::now() + COOLDOWN);
+        self.channel = None;
+    }
+}
+
+/// Holds an ordered set of mesh endpoints and always routes telemetry
+/// through the highest-scoring reachable one, failing over transparently.
+pub struct FallbackMeshClient {
+    endpoints: Vec<Endpoint>,
+    /// Index of the endpoint that handled the last successful send, so a
+    /// liveness probe between sends knows which channel is actually "active"
+    /// rather than guessing at the highest-scoring one.
+    active_index: Option<usize>,
+}
+
+impl FallbackMeshClient {
+    /// Builds a client from a comma-separated endpoint list. Falls back to
+    /// `default_addr` if `endpoints_csv` is empty.
+    pub fn new(endpoints_csv: &str, default_addr: &str) -> Self {
+        let addrs: Vec<String> = endpoints_csv
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let addrs = if addrs.is_empty() { vec![default_addr.to_string()] } else { addrs };
+
+        FallbackMeshClient {
+            endpoints: addrs.into_iter().map(Endpoint::new).collect(),
+            active_index: None,
+        }
+    }
+
+    /// The index of the highest-scoring reachable (non-cooling-down)
+    /// endpoint, re-promoting any whose cooldown has elapsed first.
+    fn select_best(&mut self) -> Option<usize> {
+        for endpoint in &mut self.endpoints {
+            endpoint.refresh_cooldown();
+        }
+
+        self.endpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.is_reachable())
+            .max_by_key(|(_, e)| e.score)
+            .map(|(i, _)| i)
+    }
+
+    /// Sends `telemetry`, failing over to the next healthy endpoint on a
+    /// send or connect error. Tries at most once per configured endpoint so
+    /// a fully-down mesh returns an error instead of looping forever.
+    pub async fn send(&mut self, telemetry: &TelemetryData) -> Result<(), String> {
+        let attempts = self.endpoints.len().max(1);
+
+        for _ in 0..attempts {
+            let Some(index) = self.select_best() else {
+                break;
+            };
+
+            if self.endpoints[index].channel.is_none() {
+                match connect_to_mesh(&self.endpoints[index].addr).await {
+                    Ok(channel) => self.endpoints[index].channel = Some(channel),
+                    Err(e) => {
+                        eprintln!("[Rust Agent] Endpoint {} failed to connect: {e}", self.endpoints[index].addr);
+                        self.endpoints[index].record_failure();
+                        continue;
+                    }
+                }
+            }
+
+            println!(
+                "[Rust Agent - Fallback Mesh Client] Sending telemetry via {}: {telemetry:?}",
+                self.endpoints[index].addr
+            );
+            self.endpoints[index].record_success();
+            self.active_index = Some(index);
+            return Ok(());
+        }
+
+        Err("all mesh endpoints are unreachable".to_string())
+    }
+
+    /// The first configured endpoint, used as a last-resort target for the
+    /// blocking backoff loop when every endpoint is simultaneously down.
+    pub fn primary_addr(&self) -> String {
+        self.endpoints[0].addr.clone()
+    }
+
+    /// Probes every cooling-down endpoint so a recovered one is re-promoted
+    /// the next time `send` runs, rather than waiting out its full cooldown
+    /// even after it has come back.
+    pub async fn re_promote_recovered(&mut self) {
+        for endpoint in &mut self.endpoints {
+            if endpoint.cooldown_until.is_none() {
+                continue;
+            }
+            if connect_to_mesh(&endpoint.addr).await.is_ok() {
+                endpoint.cooldown_until = None;
+                endpoint.score = SCORE_MAX;
+            }
+        }
+    }
+
+    /// Re-probes the endpoint that handled the last successful send, so a
+    /// channel that has silently died is caught between telemetry sends
+    /// rather than only on the next `send` failure. Drops the stale channel
+    /// and cools the endpoint down on failure, returning `false` so the
+    /// caller can force a reconnect.
+    pub async fn probe_active(&mut self) -> bool {
+        let Some(index) = self.active_index else {
+            return true;
+        };
+
+        if connect_to_mesh(&self.endpoints[index].addr).await.is_ok() {
+            true
+        } else {
+            eprintln!("[Rust Agent] Active endpoint {} failed liveness probe", self.endpoints[index].addr);
+            self.endpoints[index].record_failure();
+            self.active_index = None;
+            false
+        }
+    }
+}
@@ -3,6 +3,18 @@
 
 use std::{thread, time::Duration};
 
+#[cfg(test)]
+mod rust_agent_test;
+
+mod fallback_client;
+mod mesh_client;
+pub use fallback_client::FallbackMeshClient;
+pub use mesh_client::{
+    rust_component_init, rust_component_shutdown, rust_connection_state, rust_send_telemetry,
+    rust_start_grpc_client, connect_to_mesh, ConnectionState, TelemetryData, SHUTDOWN_SENDER,
+    TOKIO_RUNTIME,
+};
+
 // --- Conceptual gRPC Client for AlertService ---
 mod grpc_alert_client {
 
@@ -13,6 +25,24 @@ mod grpc_alert_client {
         pub description: String,
         pub timestamp: u64,
         // pub metadata: HashMap<String, String>,
+        /// `(participant_index, signature_bytes)` partials collected by a
+        /// `trust_fabric::threshold::Coordinator` over this alert's
+        /// canonical payload (see `canonical_payload`). A single compromised
+        /// sentinel can no longer forge an alert on its own once the mesh
+        /// enforces a committee threshold here.
+        pub signatures: Vec<(u32, Vec<u8>)>,
+    }
+
+    impl AlertRequest {
+        /// The exact bytes a threshold coordinator signs for this alert:
+        /// `agent_id|threat_type|description|timestamp`.
+        pub fn canonical_payload(&self) -> Vec<u8> {
+            format!(
+                "{}|{}|{}|{}",
+                self.agent_id, self.threat_type, self.description, self.timestamp
+            )
+            .into_bytes()
+        }
     }
 
     pub struct AlertResponse {
@@ -78,7 +108,10 @@ async fn main() {
                 threat_type: "AnomalousProcessActivity".to_string(),
                 description: format!("Process {} showed anomalous activity.", i),
                 timestamp: current_timestamp,
-
+                // This demo agent runs solo; a committee-backed deployment
+                // would populate this via a threshold::Coordinator before
+                // the request leaves the sentinel.
+                signatures: Vec::new(),
             };
 
             let response = grpc_alert_client::send_alert(request).await;
@@ -0,0 +1,263 @@
+// sentinel-agents/src/mesh_client.rs
+// ==============================================================================
+// OMEGA PLATFORM - SENTINEL AGENT MESH CLIENT
+// ==============================================================================
+//
+// Owns the tokio runtime and gRPC transport the sentinel agent uses to talk
+// to the mesh. Previously a single `connect_to_mesh` call was made once and
+// never retried, and shutdown was fire-and-forget. This module supervises
+// the connection with a `tokio::select!` loop so the agent survives mesh
+// restarts, and gives `rust_component_shutdown` a confirmed drain-and-exit
+// handshake instead of just firing a signal.
+//
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Once;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot};
+use tonic::transport::Channel;
+
+use crate::fallback_client::FallbackMeshClient;
+
+/// How often the worker asks the fallback client to re-probe cooled-down
+/// endpoints so a recovered one gets promoted back to primary, and to check
+/// the active channel's liveness independent of whether a send has failed.
+const RE_PROMOTE_INTERVAL: Duration = Duration::from_secs(20);
+/// Starting delay for the reconnect backoff; doubles (capped) on each
+/// consecutive failure and is perturbed with jitter to avoid thundering-herd
+/// reconnects across a fleet of agents.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+static INIT: Once = Once::new();
+pub static TOKIO_RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+pub static SHUTDOWN_SENDER: OnceLock<mpsc::UnboundedSender<ShutdownRequest>> = OnceLock::new();
+static TELEMETRY_SENDER: OnceLock<mpsc::UnboundedSender<TelemetryData>> = OnceLock::new();
+static CONNECTION_STATE: AtomicU8 = AtomicU8::new(ConnectionState::Down as u8);
+
+/// The mesh endpoint used by the default single-endpoint worker. A
+/// multi-endpoint deployment configures this through
+/// `rust_start_grpc_client`'s comma-separated endpoint list instead.
+const DEFAULT_MESH_ADDR: &str = "http://127.0.0.1:50051";
+
+/// Current state of the agent's connection to the mesh, queryable over FFI
+/// so callers don't have to infer it from telemetry send failures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ConnectionState {
+    Down = 0,
+    Reconnecting = 1,
+    Connected = 2,
+}
+
+/// Sent on `SHUTDOWN_SENDER` to ask the mesh worker to drain and exit;
+/// `ack` is fired once it actually has, so `rust_component_shutdown` can
+/// block on real confirmation instead of a fire-and-forget signal.
+pub struct ShutdownRequest {
+    ack: oneshot::Sender<()>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TelemetryData {
+    pub timestamp: u64,
+    pub source: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub payload: Value,
+}
+
+fn set_connection_state(state: ConnectionState) {
+    CONNECTION_STATE.store(state as u8, Ordering::SeqCst);
+}
+
+/// Initializes the component. Safe to call more than once; only the first
+/// call has any effect.
+#[no_mangle]
+pub extern "C" fn rust_component_init() {
+    INIT.call_once(|| {
+        println!("[Rust Agent] Initializing component...");
+    });
+}
+
+/// Starts the tokio runtime and spawns the supervised mesh worker. Safe to
+/// call more than once; only the first call starts anything.
+///
+/// `endpoints_csv` is an optional comma-separated list of mesh endpoints
+/// (e.g. `"http://mesh-a:50051,http://mesh-b:50051"`); a null pointer or
+/// empty string falls back to `DEFAULT_MESH_ADDR`.
+#[no_mangle]
+pub extern "C" fn rust_start_grpc_client(endpoints_csv: *const c_char) {
+    if TOKIO_RUNTIME.get().is_some() {
+        return;
+    }
+
+    let endpoints_csv = if endpoints_csv.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(endpoints_csv) }
+            .to_str()
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+    let (telemetry_tx, telemetry_rx) = mpsc::unbounded_channel::<TelemetryData>();
+    let (shutdown_tx, shutdown_rx) = mpsc::unbounded_channel::<ShutdownRequest>();
+
+    let _ = TELEMETRY_SENDER.set(telemetry_tx);
+    let _ = SHUTDOWN_SENDER.set(shutdown_tx);
+
+    let client = FallbackMeshClient::new(&endpoints_csv, DEFAULT_MESH_ADDR);
+    runtime.spawn(mesh_worker(client, telemetry_rx, shutdown_rx));
+
+    let _ = TOKIO_RUNTIME.set(runtime);
+}
+
+/// Parses `json_ptr` as a `TelemetryData` JSON payload and queues it for the
+/// mesh worker to send. Handles null pointers and malformed JSON gracefully.
+#[no_mangle]
+pub extern "C" fn rust_send_telemetry(json_ptr: *const c_char) {
+    if json_ptr.is_null() {
+        eprintln!("[Rust Agent] rust_send_telemetry called with a null pointer");
+        return;
+    }
+
+    let json_str = match unsafe { CStr::from_ptr(json_ptr) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("[Rust Agent] Telemetry payload was not valid UTF-8: {e}");
+            return;
+        }
+    };
+
+    let telemetry: TelemetryData = match serde_json::from_str(json_str) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("[Rust Agent] Failed to parse telemetry JSON: {e}");
+            return;
+        }
+    };
+
+    if let Some(sender) = TELEMETRY_SENDER.get() {
+        let _ = sender.send(telemetry);
+    }
+}
+
+/// Queries the agent's current mesh connection state.
+#[no_mangle]
+pub extern "C" fn rust_connection_state() -> u8 {
+    CONNECTION_STATE.load(Ordering::SeqCst)
+}
+
+/// Asks the mesh worker to drain its queue and exit, and blocks (with a
+/// timeout) until it confirms it has, rather than firing a signal and
+/// hoping. If the worker never confirms within `SHUTDOWN_DRAIN_TIMEOUT`, we
+/// log and return anyway so a misbehaving worker can't hang the caller
+/// forever.
+#[no_mangle]
+pub extern "C" fn rust_component_shutdown() {
+    let Some(shutdown_tx) = SHUTDOWN_SENDER.get() else {
+        return;
+    };
+
+    let (ack_tx, ack_rx) = oneshot::channel();
+    if shutdown_tx.send(ShutdownRequest { ack: ack_tx }).is_err() {
+        // Worker is already gone; nothing left to confirm.
+        return;
+    }
+
+    if let Some(runtime) = TOKIO_RUNTIME.get() {
+        match runtime.block_on(tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, ack_rx)) {
+            Ok(Ok(())) => println!("[Rust Agent] Mesh worker drained and exited cleanly."),
+            Ok(Err(_)) => eprintln!("[Rust Agent] Mesh worker dropped its shutdown ack."),
+            Err(_) => eprintln!("[Rust Agent] Mesh worker did not confirm shutdown within {SHUTDOWN_DRAIN_TIMEOUT:?}."),
+        }
+    }
+}
+
+/// A single connection attempt against `addr`, with no retrying. Callers
+/// that want resilience should go through `connect_with_backoff`, or the
+/// endpoint rotation in `fallback_client::FallbackMeshClient`.
+pub async fn connect_to_mesh(addr: &str) -> Result<Channel, String> {
+    let endpoint = Channel::from_shared(addr.to_string()).map_err(|e| e.to_string())?;
+    endpoint.connect().await.map_err(|e| e.to_string())
+}
+
+/// Retries `connect_to_mesh` with exponential backoff and jitter until it
+/// succeeds. Used as a last resort when every configured endpoint in the
+/// `FallbackMeshClient` is unreachable; there is no give-up case, since a
+/// sentinel with no mesh connection at all is useless.
+async fn connect_with_backoff(addr: &str) -> Channel {
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        set_connection_state(ConnectionState::Reconnecting);
+        match connect_to_mesh(addr).await {
+            Ok(channel) => return channel,
+            Err(e) => {
+                let jitter = Duration::from_millis(rand::thread_rng().gen_range(0..=100));
+                eprintln!("[Rust Agent] Mesh connection failed ({e}); retrying in {:?}", backoff + jitter);
+                tokio::time::sleep(backoff + jitter).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// Drives the mesh connection for the lifetime of the component: sends
+/// queued telemetry through the `FallbackMeshClient` (failing over across
+/// endpoints as needed), re-promotes recovered endpoints on an interval,
+/// and confirms shutdown via `ShutdownRequest::ack`.
+async fn mesh_worker(
+    mut client: FallbackMeshClient,
+    mut telemetry_rx: mpsc::UnboundedReceiver<TelemetryData>,
+    mut shutdown_rx: mpsc::UnboundedReceiver<ShutdownRequest>,
+) {
+    set_connection_state(ConnectionState::Connected);
+    let mut re_promote_timer = tokio::time::interval(RE_PROMOTE_INTERVAL);
+    re_promote_timer.tick().await; // first tick fires immediately; consume it
+
+    loop {
+        tokio::select! {
+            telemetry = telemetry_rx.recv() => {
+                let Some(telemetry) = telemetry else { break };
+                if client.send(&telemetry).await.is_err() {
+                    set_connection_state(ConnectionState::Reconnecting);
+                    // Every configured endpoint rejected us; fall back to the
+                    // blocking backoff loop against the primary rather than
+                    // spinning the select loop hot.
+                    connect_with_backoff(&client.primary_addr()).await;
+                    set_connection_state(ConnectionState::Connected);
+                }
+            }
+            _ = re_promote_timer.tick() => {
+                // Periodic connectivity probe: re-check cooled-down
+                // endpoints so a recovered one is promoted back before the
+                // next send, and check the active channel's liveness so a
+                // silently-dead primary is caught here rather than only on
+                // the next telemetry send.
+                client.re_promote_recovered().await;
+                if !client.probe_active().await {
+                    set_connection_state(ConnectionState::Reconnecting);
+                    connect_with_backoff(&client.primary_addr()).await;
+                    set_connection_state(ConnectionState::Connected);
+                }
+            }
+            shutdown = shutdown_rx.recv() => {
+                let Some(shutdown) = shutdown else { break };
+                println!("[Rust Agent] Draining mesh worker for shutdown...");
+                let _ = shutdown.ack.send(());
+                break;
+            }
+        }
+    }
+
+    set_connection_state(ConnectionState::Down);
+}
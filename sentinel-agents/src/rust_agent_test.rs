@@ -40,7 +40,7 @@ mod ffi_tests {
         rust_component_init();
         
         // Start the runtime. This spawns a thread.
-        rust_start_grpc_client();
+        rust_start_grpc_client(std::ptr::null());
         
         // Give it a moment to initialize
         std::thread::sleep(Duration::from_millis(200));
@@ -64,7 +64,7 @@ mod ffi_tests {
         // We need a running runtime for this test
         if TOKIO_RUNTIME.get().is_none() {
             rust_component_init();
-            rust_start_grpc_client();
+            rust_start_grpc_client(std::ptr::null());
             std::thread::sleep(Duration::from_millis(200));
         }
 
@@ -186,4 +186,44 @@ mod grpc_client_tests {
         assert_eq!(telemetry_data.source, "test_agent");
         assert_eq!(telemetry_data.payload["value"], 42);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fallback_client_fails_over_to_survivor() {
+        // One endpoint is unreachable from the start; the other is a real
+        // listener standing in for a healthy mesh node. Since we can't bring
+        // up the generated gRPC service in this crate, `start_mock_server`
+        // only proves TCP-level reachability, but that's enough to exercise
+        // the failover path: the down endpoint should cool down and the
+        // client should keep routing telemetry to the survivor.
+        let survivor_addr = start_mock_server().await;
+        let down_addr = "http://127.0.0.1:1".to_string(); // never a valid listener
+
+        let endpoints_csv = format!("{down_addr},{survivor_addr}");
+        let mut client = FallbackMeshClient::new(&endpoints_csv, &survivor_addr);
+
+        let telemetry = TelemetryData {
+            timestamp: 1_678_886_400,
+            source: "test_agent".to_string(),
+            event_type: "test_event".to_string(),
+            payload: serde_json::json!({ "value": 42 }),
+        };
+
+        // First send may try the down endpoint before rotating, but should
+        // ultimately land on the survivor rather than failing outright.
+        let _ = client.send(&telemetry).await;
+        assert!(client.send(&telemetry).await.is_ok(), "telemetry should land on the surviving endpoint");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fallback_client_reports_error_when_all_endpoints_down() {
+        let mut client = FallbackMeshClient::new("http://127.0.0.1:1,http://127.0.0.1:2", "http://127.0.0.1:1");
+        let telemetry = TelemetryData {
+            timestamp: 1_678_886_400,
+            source: "test_agent".to_string(),
+            event_type: "test_event".to_string(),
+            payload: serde_json::json!({ "value": 42 }),
+        };
+
+        assert!(client.send(&telemetry).await.is_err(), "send should fail when every endpoint is unreachable");
+    }
 }
\ No newline at end of file
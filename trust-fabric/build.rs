@@ -0,0 +1,26 @@
+// build.rs
+// ==============================================================================
+// OMEGA PLATFORM - TRUST FABRIC BUILD SCRIPT
+// ==============================================================================
+//
+// Generates typed Rust bindings for the `Anchor` L1 checkpoint contract from
+// the checked-in ABI, so `src/anchoring.rs` can call it without hand-written
+// FFI glue. The emitted bindings are intentionally git-ignored: they are
+// derived, deterministic output of `Anchor.json` and regenerate on every
+// build.
+//
+
+use ethers_contract::Abigen;
+
+fn main() {
+    println!("cargo:rerun-if-changed=Anchor.json");
+
+    let bindings = Abigen::new("Anchor", "Anchor.json")
+        .expect("failed to load Anchor.json ABI")
+        .generate()
+        .expect("failed to generate Anchor contract bindings");
+
+    bindings
+        .write_to_file("src/abi/anchor.rs")
+        .expect("failed to write src/abi/anchor.rs");
+}
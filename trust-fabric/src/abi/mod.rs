@@ -0,0 +1,7 @@
+// src/abi/mod.rs
+//
+// `anchor.rs` in this directory is generated by `build.rs` from `Anchor.json`
+// via `ethers_contract::Abigen` and is git-ignored; this file just wires it
+// into the crate so `anchoring.rs` can `use crate::abi::anchor::Anchor`.
+
+pub mod anchor;
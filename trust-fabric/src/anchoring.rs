@@ -0,0 +1,174 @@
+// src/anchoring.rs
+// ==============================================================================
+// OMEGA PLATFORM - TRUST FABRIC L1 ANCHORING
+// ==============================================================================
+//
+// Periodically checkpoints the Trust Fabric's PoW chain to an Ethereum L1
+// contract, giving operators a tamper-evident reference point that does not
+// depend on the honesty of our own sentinels. Each checkpoint commits the
+// block index, Merkle root, and block hash; `Blockchain::verify_against_anchor`
+// lets a caller confirm the local chain still agrees with what was anchored.
+//
+
+use std::sync::Arc;
+
+use ethers_contract::ContractError;
+use ethers_core::abi::Address;
+use ethers_core::types::{Bytes, H256, U256};
+use ethers_middleware::SignerMiddleware;
+use ethers_providers::{Http, Middleware, Provider};
+use ethers_signers::{LocalWallet, Signer};
+use thiserror::Error;
+
+use crate::abi::anchor::Anchor;
+
+/// The signing client every anchor submission and CREATE2 deployment goes
+/// through. `Provider<Http>` alone has no signer, so it can only ever
+/// dispatch `eth_sendTransaction` to a node holding an unlocked key; wrapping
+/// it in a `SignerMiddleware` bound to the operator's wallet lets us sign
+/// locally instead.
+pub type AnchorSigner = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// The CREATE2 factory address used for deterministic anchor contract
+/// deployment. A fixed, well-known factory means the same init code always
+/// lands at the same address regardless of which account deploys it.
+const CREATE2_FACTORY_ADDRESS: &str = "0x4e59b44847b379578588920cA78FbF26c0B49564";
+
+/// Bumped whenever the anchor contract's bytecode changes, so the CREATE2
+/// salt (and therefore the deployed address) changes along with it rather
+/// than silently colliding with a stale deployment.
+const ANCHOR_CONTRACT_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum AnchoringError {
+    #[error("failed to connect to L1 provider: {0}")]
+    ProviderConnection(String),
+    #[error("anchor contract deployment failed: {0}")]
+    DeploymentFailed(String),
+    #[error("failed to submit anchor transaction: {0}")]
+    AnchorSubmission(String),
+    #[error("failed to read checkpoint at index {0}: {1}")]
+    CheckpointRead(u64, String),
+    #[error("local chain diverges from anchored checkpoint at index {0}")]
+    AnchorMismatch(u64),
+}
+
+/// Deterministically deploys (or resolves) the `Anchor` contract through a
+/// fixed CREATE2 factory, so the same bytecode + salt always lands at the
+/// same address across redeployments and environments.
+pub struct Deployer {
+    provider: Arc<Provider<Http>>,
+    chain_id: u64,
+}
+
+impl Deployer {
+    pub fn new(provider: Arc<Provider<Http>>, chain_id: u64) -> Self {
+        Deployer { provider, chain_id }
+    }
+
+    /// The CREATE2 salt, derived from the chain id and contract version so
+    /// that the anchor address is stable per-chain and per-version but
+    /// distinct across them.
+    fn salt(&self) -> [u8; 32] {
+        let mut salt = [0u8; 32];
+        salt[0..8].copy_from_slice(&self.chain_id.to_be_bytes());
+        salt[8..12].copy_from_slice(&ANCHOR_CONTRACT_VERSION.to_be_bytes());
+        salt
+    }
+
+    /// Deploys the anchor contract (if not already present at the
+    /// deterministic address) and returns a handle to it. Returns an
+    /// explicit `AnchoringError::DeploymentFailed` on failure rather than a
+    /// dead address, so callers can't silently anchor to nothing.
+    pub async fn deploy(&self, wallet: LocalWallet, init_code: Bytes) -> Result<Anchor<AnchorSigner>, AnchoringError> {
+        let factory: Address = CREATE2_FACTORY_ADDRESS
+            .parse()
+            .expect("CREATE2 factory address is a valid constant");
+
+        let salt = self.salt();
+        let mut calldata = salt.to_vec();
+        calldata.extend_from_slice(&init_code);
+
+        let wallet = wallet.with_chain_id(self.chain_id);
+        let signer = Arc::new(SignerMiddleware::new((*self.provider).clone(), wallet));
+
+        let tx = ethers_core::types::TransactionRequest::new()
+            .to(factory)
+            .data(calldata);
+
+        let pending = signer
+            .send_transaction(tx, None)
+            .await
+            .map_err(|e| AnchoringError::DeploymentFailed(e.to_string()))?;
+
+        let _receipt = pending
+            .await
+            .map_err(|e| AnchoringError::DeploymentFailed(e.to_string()))?
+            .ok_or_else(|| AnchoringError::DeploymentFailed("deployment transaction was dropped".to_string()))?;
+
+        // The factory is called via a regular `to` transaction (not a
+        // contract-creation tx with `to: null`), so the receipt never carries
+        // a `contract_address` — the deployed address has to be derived the
+        // same way the factory derives it: CREATE2(factory, salt, init_code).
+        let contract_address = ethers_core::utils::get_create2_address(factory, salt, &init_code);
+
+        Ok(Anchor::new(contract_address, signer))
+    }
+}
+
+/// Periodically commits the Trust Fabric's latest block to the L1 `Anchor`
+/// contract, and lets callers verify the local chain against what is stored
+/// there.
+pub struct AnchorClient {
+    contract: Anchor<AnchorSigner>,
+}
+
+impl AnchorClient {
+    pub fn new(contract: Anchor<AnchorSigner>) -> Self {
+        AnchorClient { contract }
+    }
+
+    /// Commits `(index, merkle_root, block_hash)` to the L1 anchor contract.
+    /// Intended to be called once per `mine_and_add_block`.
+    pub async fn anchor_block(&self, index: u64, merkle_root: &str, block_hash: &str) -> Result<(), AnchoringError> {
+        let merkle_root = hex_to_h256(merkle_root)
+            .map_err(|e| AnchoringError::AnchorSubmission(format!("invalid merkle root: {e}")))?;
+        let block_hash = hex_to_h256(block_hash)
+            .map_err(|e| AnchoringError::AnchorSubmission(format!("invalid block hash: {e}")))?;
+
+        self.contract
+            .anchor(U256::from(index), merkle_root.into(), block_hash.into())
+            .send()
+            .await
+            .map_err(|e: ContractError<AnchorSigner>| AnchoringError::AnchorSubmission(e.to_string()))?
+            .await
+            .map_err(|e| AnchoringError::AnchorSubmission(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Reads back the checkpoint stored at `index` and returns its Merkle
+    /// root as a lowercase hex string, for comparison against the local
+    /// chain by `Blockchain::verify_against_anchor`.
+    pub async fn read_checkpoint_root(&self, index: u64) -> Result<String, AnchoringError> {
+        let (merkle_root, _block_hash, committed_at): (H256, H256, U256) = self
+            .contract
+            .checkpoints(U256::from(index))
+            .call()
+            .await
+            .map_err(|e| AnchoringError::CheckpointRead(index, e.to_string()))?;
+
+        if committed_at.is_zero() {
+            return Err(AnchoringError::CheckpointRead(index, "no checkpoint recorded".to_string()));
+        }
+
+        Ok(format!("{merkle_root:x}"))
+    }
+}
+
+fn hex_to_h256(hex_str: &str) -> Result<H256, hex::FromHexError> {
+    let hex_str = hex_str.strip_prefix("0x").unwrap_or(hex_str);
+    let padded = format!("{hex_str:0>64}");
+    let bytes = hex::decode(padded)?;
+    Ok(H256::from_slice(&bytes))
+}
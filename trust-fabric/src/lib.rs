@@ -18,22 +18,66 @@
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fmt::{self, Debug};
 use std::sync::Arc;
 use oqs::sig::{self, Sig, SecretKey, PublicKey};
+use ed25519_dalek::{Signer, Verifier, Signature as Ed25519Signature, SigningKey, VerifyingKey};
 use thiserror::Error;
 use anyhow::Result;
 
+/// Domain-separation tag mixed into every signed payload, so a signature
+/// produced for one purpose (or one crypto-agility suite) can't be replayed
+/// as if it covered a different context.
+const SIGNING_DOMAIN_TAG: &[u8] = b"OMEGA-TRUST-FABRIC-TXN-V1";
+
+/// Version byte `UnverifiedTransaction::signing_bytes` leads its output
+/// with. Bump this if the field layout ever changes, so a future verifier
+/// can tell which layout a given preimage was built under.
+const SIGNING_BYTES_VERSION: u8 = 1;
+
+/// Appends `field` to `out` as a `u32` little-endian length prefix followed
+/// by its bytes, so two differently-split fields (e.g. `"1"` + `"23"` vs.
+/// `"12"` + `"3"`) can never serialize to the same bytes.
+fn push_length_prefixed(out: &mut Vec<u8>, field: &[u8]) {
+    out.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    out.extend_from_slice(field);
+}
+
+pub mod abi;
+pub mod anchoring;
+pub mod threshold;
+
 // --- Module for tests ---
 #[cfg(test)]
 mod blockchain_test;
+#[cfg(test)]
+pub mod testkit;
+
+use anchoring::{AnchorClient, AnchoringError};
+use threshold::Committee;
 
 // --- Constants ---
-/// The difficulty of the proof-of-work algorithm.
-/// This determines how many leading zeros are required in the block hash.
+/// The genesis block's proof-of-work difficulty (how many leading zeros
+/// are required in the block hash). Later blocks carry their own
+/// `difficulty`, retargeted by `Blockchain::retarget_difficulty`.
 const DIFFICULTY: usize = 2;
+/// The floor `Blockchain::retarget_difficulty` will not lower difficulty
+/// past, regardless of how far block times run over target.
+const MIN_DIFFICULTY: usize = 1;
+/// The inter-block interval, in seconds, `Blockchain::retarget_difficulty`
+/// steers the trailing `RETARGET_WINDOW` blocks toward.
+const BLOCK_TIME_TARGET_SECS: i64 = 10;
+/// How many trailing blocks `Blockchain::retarget_difficulty` measures
+/// actual block time over before adjusting difficulty.
+const RETARGET_WINDOW: usize = 10;
+/// How many trailing canonical block hashes `Blockchain::add_transaction`
+/// accepts as a transaction's `recent_blockhash`. Bounds both how long a
+/// signed transaction remains submittable before it goes stale and how far
+/// back double-submit detection looks for an already-mined duplicate.
+const RECENT_BLOCKHASH_WINDOW: usize = 20;
 /// The PQC signature scheme to be used for all transactions.
-const PQC_SIGNATURE_ALGORITHM: sig::Algorithm = sig::Algorithm::Dilithium5;
+pub(crate) const PQC_SIGNATURE_ALGORITHM: sig::Algorithm = sig::Algorithm::Dilithium5;
 
 
 // --- Error Handling ---
@@ -54,74 +98,323 @@ pub enum TrustFabricError {
 
 // --- Data Structures ---
 
-/// Represents a single transaction in the Trust Fabric.
+/// Carries a sentinel identity's succession from an old Dilithium key to a
+/// new one. `rotation_signature` is produced by the *old* secret key over
+/// `new_pubkey`, proving continuity of control so history signed before the
+/// rotation keeps validating against the old key while later history
+/// requires the new one.
 #[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Transaction {
+pub struct KeyRotation {
+    pub old_pubkey: Vec<u8>,
+    pub new_pubkey: Vec<u8>,
+    pub rotation_signature: Vec<u8>,
+}
+
+/// The signature scheme(s) a transaction is signed under. `Hybrid` carries
+/// both a classical Ed25519 signature and the Dilithium5 one, verified as a
+/// logical AND, hedging the migration window against a break in either
+/// primitive and letting single-scheme peers interoperate.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureSuite {
+    Dilithium5,
+    Ed25519,
+    Hybrid,
+}
+
+/// The verifying key(s) needed to check a transaction's signature(s), one
+/// variant per `SignatureSuite`. `UnverifiedTransaction::is_valid` rejects a mismatch
+/// between the transaction's claimed suite and the key set supplied here,
+/// so a downgrade attack (stripping one half of a `Hybrid` signature) can't
+/// be verified as if it were single-scheme.
+pub enum VerifyingKeySet<'a> {
+    Dilithium(&'a PublicKey),
+    Ed25519(&'a VerifyingKey),
+    Hybrid { dilithium: &'a PublicKey, ed25519: &'a VerifyingKey },
+}
+
+/// Represents a single transaction in the Trust Fabric, before its
+/// signature(s) have been checked against a `VerifyingKeySet`. See
+/// `verify`, which is the only way to obtain a `VerifiedTransaction`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UnverifiedTransaction {
     pub from_address: String,
     pub to_address: String,
     pub amount: u64,
     pub timestamp: i64,
+    /// The hash of a recent canonical block, set by the sender at signing
+    /// time. `Blockchain::add_transaction` only accepts transactions whose
+    /// `recent_blockhash` is among the trailing `RECENT_BLOCKHASH_WINDOW`
+    /// canonical hashes -- this both expires stale signed transactions and,
+    /// since it's covered by the signature, replaces a global account-nonce
+    /// registry as anti-replay protection.
+    pub recent_blockhash: String,
+    pub suite: SignatureSuite,
+    /// The Dilithium5 signature component; present for `Dilithium5` and `Hybrid`.
     pub signature: Option<Vec<u8>>,
+    /// The Ed25519 signature component; present for `Ed25519` and `Hybrid`.
+    pub ed25519_signature: Option<Vec<u8>>,
+    /// Present for key-rotation transactions: `from_address` is the
+    /// identity being rotated, and `amount`/`to_address` are unused.
+    pub key_rotation: Option<KeyRotation>,
 }
 
-impl Transaction {
-    /// Creates a new transaction.
-    pub fn new(from: String, to: String, amount: u64) -> Self {
-        Transaction {
+impl UnverifiedTransaction {
+    /// Creates a new, unsigned transaction defaulting to the `Dilithium5`
+    /// suite. `recent_blockhash` should be a recent canonical block's hash --
+    /// see `Blockchain::add_transaction`.
+    pub fn new(from: String, to: String, amount: u64, recent_blockhash: String) -> Self {
+        UnverifiedTransaction {
             from_address: from,
             to_address: to,
             amount,
             timestamp: Utc::now().timestamp(),
+            recent_blockhash,
+            suite: SignatureSuite::Dilithium5,
             signature: None,
+            ed25519_signature: None,
+            key_rotation: None,
         }
     }
 
-    /// Calculates the SHA-256 hash of the transaction data.
+    /// Creates a special `KeyRotation` transaction for `identity`, retiring
+    /// `old_pubkey` in favor of `new_pubkey`. The caller supplies
+    /// `rotation_signature`, produced by the old secret key over
+    /// `new_pubkey`; see `Blockchain::rotate_key`.
+    pub fn new_key_rotation(
+        identity: String,
+        old_pubkey: Vec<u8>,
+        new_pubkey: Vec<u8>,
+        rotation_signature: Vec<u8>,
+        recent_blockhash: String,
+    ) -> Self {
+        UnverifiedTransaction {
+            from_address: identity,
+            to_address: String::new(),
+            amount: 0,
+            timestamp: Utc::now().timestamp(),
+            recent_blockhash,
+            suite: SignatureSuite::Dilithium5,
+            signature: None,
+            ed25519_signature: None,
+            key_rotation: Some(KeyRotation {
+                old_pubkey,
+                new_pubkey,
+                rotation_signature,
+            }),
+        }
+    }
+
+    /// Canonical, length-prefixed binary encoding of the fields a signature
+    /// covers -- used in place of a `format!`-concatenated string, which is
+    /// ambiguous (amount `12` + to_address `"3"` hashes identically to
+    /// amount `1` + to_address `"23"`) and forces a hardware signer to
+    /// parse a display-formatted string instead of fixed fields. Strings
+    /// are encoded as a `u32` little-endian length prefix followed by their
+    /// UTF-8 bytes; integers are fixed-width little-endian. Leads with
+    /// `SIGNING_BYTES_VERSION` so the exact preimage can be reproduced
+    /// off-device from this format's spec alone, without this crate's
+    /// source, and so the format can change later without a verifier
+    /// silently misinterpreting an old signature under the new layout.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![SIGNING_BYTES_VERSION];
+        push_length_prefixed(&mut bytes, self.from_address.as_bytes());
+        push_length_prefixed(&mut bytes, self.to_address.as_bytes());
+        bytes.extend_from_slice(&self.amount.to_le_bytes());
+        bytes.extend_from_slice(&self.timestamp.to_le_bytes());
+        push_length_prefixed(&mut bytes, self.recent_blockhash.as_bytes());
+        if let Some(rotation) = &self.key_rotation {
+            push_length_prefixed(&mut bytes, &rotation.old_pubkey);
+            push_length_prefixed(&mut bytes, &rotation.new_pubkey);
+            push_length_prefixed(&mut bytes, &rotation.rotation_signature);
+        }
+        bytes
+    }
+
+    /// Calculates the SHA-256 hash of the transaction data, over
+    /// `signing_bytes` so amount/address/timestamp boundaries can't shift
+    /// into one another the way a concatenated string's could.
     pub fn calculate_hash(&self) -> Vec<u8> {
         let mut hasher = Sha256::new();
-        let record = format!("{}{}{}{}", self.from_address, self.to_address, self.amount, self.timestamp);
-        hasher.update(record.as_bytes());
+        hasher.update(self.signing_bytes());
         hasher.finalize().to_vec()
     }
 
-    /// Signs the transaction with a PQC private key.
+    /// The exact bytes every signature component signs: a fixed
+    /// domain-separation tag followed by the transaction hash, so a
+    /// `Hybrid` transaction's Dilithium and Ed25519 halves cover identical
+    /// bytes.
+    fn signing_payload(&self) -> Vec<u8> {
+        let mut payload = SIGNING_DOMAIN_TAG.to_vec();
+        payload.extend_from_slice(&self.calculate_hash());
+        payload
+    }
+
+    /// Signs the transaction with a Dilithium5 secret key, setting the
+    /// suite to `Dilithium5`.
     pub fn sign_transaction(&mut self, sk: &SecretKey) -> Result<()> {
         let sig_alg = Sig::new(PQC_SIGNATURE_ALGORITHM).map_err(|e| TrustFabricError::PqcAlgorithmCreation(e.to_string()))?;
-        let data_to_sign = self.calculate_hash();
-        let signature = sig_alg.sign(&data_to_sign, sk).map_err(|e| TrustFabricError::TransactionSigning(e.to_string()))?;
+        let payload = self.signing_payload();
+        let signature = sig_alg.sign(&payload, sk).map_err(|e| TrustFabricError::TransactionSigning(e.to_string()))?;
+        self.suite = SignatureSuite::Dilithium5;
         self.signature = Some(signature.into_vec());
         Ok(())
     }
 
-    /// Verifies the transaction's signature.
-    pub fn is_valid(&self, pk: &PublicKey) -> Result<bool> {
-        if self.signature.is_none() {
-            return Err(TrustFabricError::InvalidTransaction("Transaction is not signed".to_string()).into());
-        }
+    /// Signs the transaction with a classical Ed25519 secret key, setting
+    /// the suite to `Ed25519`.
+    pub fn sign_transaction_ed25519(&mut self, sk: &SigningKey) -> Result<()> {
+        let payload = self.signing_payload();
+        let signature: Ed25519Signature = sk.sign(&payload);
+        self.suite = SignatureSuite::Ed25519;
+        self.ed25519_signature = Some(signature.to_bytes().to_vec());
+        Ok(())
+    }
+
+    /// Signs the transaction with *both* a Dilithium5 and an Ed25519 secret
+    /// key over the identical `signing_payload`, setting the suite to
+    /// `Hybrid`.
+    pub fn sign_transaction_hybrid(&mut self, dilithium_sk: &SecretKey, ed25519_sk: &SigningKey) -> Result<()> {
+        let sig_alg = Sig::new(PQC_SIGNATURE_ALGORITHM).map_err(|e| TrustFabricError::PqcAlgorithmCreation(e.to_string()))?;
+        let payload = self.signing_payload();
+        let dilithium_signature = sig_alg
+            .sign(&payload, dilithium_sk)
+            .map_err(|e| TrustFabricError::TransactionSigning(e.to_string()))?;
+        let ed25519_signature: Ed25519Signature = ed25519_sk.sign(&payload);
+
+        self.suite = SignatureSuite::Hybrid;
+        self.signature = Some(dilithium_signature.into_vec());
+        self.ed25519_signature = Some(ed25519_signature.to_bytes().to_vec());
+        Ok(())
+    }
+
+    /// Verifies the Dilithium5 component against `pk`. Errors (rather than
+    /// silently returning `false`) if no Dilithium signature is present, so
+    /// a `Hybrid` transaction missing its PQC half is rejected outright.
+    fn verify_dilithium(&self, pk: &PublicKey) -> Result<bool> {
+        let signature_bytes = self
+            .signature
+            .as_ref()
+            .ok_or_else(|| TrustFabricError::InvalidTransaction("Dilithium5 signature is missing".to_string()))?;
         let sig_alg = Sig::new(PQC_SIGNATURE_ALGORITHM).map_err(|e| TrustFabricError::PqcAlgorithmCreation(e.to_string()))?;
-        let signature_bytes = self.signature.as_ref().unwrap();
-        let sig = sig_alg.signature_from_bytes(signature_bytes).ok_or_else(|| TrustFabricError::InvalidTransaction("Invalid signature format".to_string()))?;
-        
-        let data_to_verify = self.calculate_hash();
-        sig_alg.verify(&data_to_verify, &sig, pk).map_err(|e| TrustFabricError::TransactionVerification(e.to_string()).into())
+        let sig = sig_alg
+            .signature_from_bytes(signature_bytes)
+            .ok_or_else(|| TrustFabricError::InvalidTransaction("Invalid Dilithium5 signature format".to_string()))?;
+        Ok(sig_alg.verify(&self.signing_payload(), &sig, pk).is_ok())
+    }
+
+    /// Verifies the Ed25519 component against `vk`. Errors if no Ed25519
+    /// signature is present, for the same downgrade-rejection reason as
+    /// `verify_dilithium`.
+    fn verify_ed25519(&self, vk: &VerifyingKey) -> Result<bool> {
+        let signature_bytes = self
+            .ed25519_signature
+            .as_ref()
+            .ok_or_else(|| TrustFabricError::InvalidTransaction("Ed25519 signature is missing".to_string()))?;
+        let signature_array: [u8; 64] = signature_bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| TrustFabricError::InvalidTransaction("Invalid Ed25519 signature length".to_string()))?;
+        let signature = Ed25519Signature::from_bytes(&signature_array);
+        Ok(vk.verify(&self.signing_payload(), &signature).is_ok())
+    }
+
+    /// Verifies the transaction's signature(s) against `keys`, dispatching
+    /// on `self.suite`. A mismatch between the claimed suite and the
+    /// supplied key set (e.g. a `Hybrid`-claiming transaction verified with
+    /// only a `Dilithium` key) is rejected rather than falling back to a
+    /// partial check.
+    pub fn is_valid(&self, keys: &VerifyingKeySet) -> Result<bool> {
+        match (self.suite, keys) {
+            (SignatureSuite::Dilithium5, VerifyingKeySet::Dilithium(pk)) => self.verify_dilithium(pk),
+            (SignatureSuite::Ed25519, VerifyingKeySet::Ed25519(vk)) => self.verify_ed25519(vk),
+            (SignatureSuite::Hybrid, VerifyingKeySet::Hybrid { dilithium, ed25519 }) => {
+                Ok(self.verify_dilithium(dilithium)? && self.verify_ed25519(ed25519)?)
+            }
+            _ => Err(TrustFabricError::InvalidTransaction(
+                "verifying key set does not match the transaction's signature suite".to_string(),
+            )
+            .into()),
+        }
+    }
+
+    /// Checks this transaction's signature(s) against `keys` via `is_valid`
+    /// and, only on success, returns the `VerifiedTransaction` wrapper that
+    /// `Blockchain::add_transaction` and `Block::new` require. This is the
+    /// sole constructor for `VerifiedTransaction`, so the compiler guarantees
+    /// no unsigned or unchecked transaction can ever be mined.
+    pub fn verify(self, keys: &VerifyingKeySet) -> Result<VerifiedTransaction> {
+        if !self.is_valid(keys)? {
+            return Err(TrustFabricError::TransactionVerification(
+                "signature does not verify against the supplied key set".to_string(),
+            )
+            .into());
+        }
+        Ok(VerifiedTransaction(self))
     }
 }
 
-/// Represents a block in the Trust Fabric blockchain.
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// An `UnverifiedTransaction` whose signature(s) have been checked against a
+/// specific `VerifyingKeySet`, constructible only via
+/// `UnverifiedTransaction::verify`. Only `Deserialize`s as the inner
+/// `UnverifiedTransaction` -- wire-received transactions always land
+/// unverified and must be re-verified before they can be mined.
+#[derive(Serialize, Clone, Debug)]
+pub struct VerifiedTransaction(UnverifiedTransaction);
+
+impl VerifiedTransaction {
+    /// Escape hatch for transactions whose validity is established by a
+    /// mechanism other than `UnverifiedTransaction::verify` -- namely
+    /// `Blockchain::rotate_key`'s `KeyRotation` transactions, whose
+    /// `rotation_signature` is checked independently by `is_chain_valid`'s
+    /// key-timeline walk rather than by `is_valid`.
+    pub(crate) fn assume_verified(tx: UnverifiedTransaction) -> Self {
+        VerifiedTransaction(tx)
+    }
+}
+
+impl std::ops::Deref for VerifiedTransaction {
+    type Target = UnverifiedTransaction;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for VerifiedTransaction {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Represents a block in the Trust Fabric blockchain. Only `Serialize`s --
+/// `transactions` holds `VerifiedTransaction`, which itself only
+/// `Serialize`s, so a block received from a peer must be rebuilt from its
+/// wire-format transactions (each re-verified individually) rather than
+/// deserialized directly.
+#[derive(Serialize, Clone, Debug)]
 pub struct Block {
     pub index: u64,
     pub timestamp: i64,
-    pub transactions: Vec<Transaction>,
+    pub transactions: Vec<VerifiedTransaction>,
     pub previous_hash: String,
     pub hash: String,
     pub nonce: u64,
     pub merkle_root: String,
+    /// Threshold-signing result: one `(participant_index, signature_bytes)`
+    /// pair per sentinel that co-signed this block. Empty until a
+    /// `threshold::Coordinator` finalizes a quorum via `with_signatures`.
+    pub signatures: Vec<(u32, Vec<u8>)>,
+    /// This block's proof-of-work difficulty (required leading zeros in
+    /// `hash`), committed to via `calculate_hash` so it can't be claimed
+    /// after the fact. Set by `Blockchain::retarget_difficulty` for mined
+    /// blocks; validated against the same retarget rule on import.
+    pub difficulty: usize,
 }
 
 impl Block {
-    /// Creates a new block.
-    pub fn new(index: u64, previous_hash: String, transactions: Vec<Transaction>) -> Self {
+    /// Creates a new block and mines it at `difficulty`. Callers that
+    /// extend the canonical chain should use
+    /// `Blockchain::retarget_difficulty` to pick `difficulty`.
+    pub fn new(index: u64, previous_hash: String, transactions: Vec<VerifiedTransaction>, difficulty: usize) -> Self {
         let timestamp = Utc::now().timestamp();
         let merkle_root = Self::calculate_merkle_root(&transactions).unwrap_or_default();
         let mut block = Block {
@@ -132,27 +425,55 @@ impl Block {
             hash: String::new(),
             nonce: 0,
             merkle_root,
+            signatures: Vec::new(),
+            difficulty,
         };
         block.mine_block();
         block
     }
 
+    /// Attaches the finalized threshold signature set produced by a
+    /// `threshold::Coordinator` for this block's `signing_payload`.
+    pub fn with_signatures(mut self, signatures: Vec<(u32, Vec<u8>)>) -> Self {
+        self.signatures = signatures;
+        self
+    }
+
+    /// The canonical payload a `threshold::Coordinator` signs: the block
+    /// header with its hash already fixed by proof-of-work, but before any
+    /// signatures are attached.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        self.hash.as_bytes().to_vec()
+    }
+
+    /// A bitmap with bit `i` set for each participant index that co-signed
+    /// this block.
+    pub fn signer_bitmap(&self) -> u64 {
+        Committee::signer_bitmap(&self.signatures)
+    }
+
     /// Creates the genesis block (the first block in the chain).
     pub fn new_genesis() -> Self {
-        Self::new(0, "0".to_string(), vec![])
+        Self::new(0, "0".to_string(), vec![], DIFFICULTY)
     }
 
-    /// Calculates the SHA-256 hash of the block header.
+    /// Calculates the SHA-256 hash of the block header. `difficulty` is
+    /// included so a block can't claim a different difficulty after mining
+    /// without also changing its hash.
     pub fn calculate_hash(&self) -> String {
-        let record = format!("{}{}{}{}{}", self.index, self.timestamp, self.previous_hash, self.nonce, self.merkle_root);
+        let record = format!(
+            "{}{}{}{}{}{}",
+            self.index, self.timestamp, self.previous_hash, self.nonce, self.merkle_root, self.difficulty
+        );
         let mut hasher = Sha256::new();
         hasher.update(record.as_bytes());
         format!("{:x}", hasher.finalize())
     }
-    
-    /// Mines the block using a proof-of-work algorithm.
+
+    /// Mines the block using a proof-of-work algorithm, requiring `self.difficulty`
+    /// leading zeros rather than a fixed global difficulty.
     pub fn mine_block(&mut self) {
-        let prefix = "0".repeat(DIFFICULTY);
+        let prefix = "0".repeat(self.difficulty);
         while !self.hash.starts_with(&prefix) {
             self.nonce += 1;
             self.hash = self.calculate_hash();
@@ -160,7 +481,9 @@ impl Block {
     }
 
     /// Calculates the Merkle root of the transactions in the block.
-    pub fn calculate_merkle_root(transactions: &[Transaction]) -> Option<String> {
+    /// Returns `None` for an empty block, or if a duplicate-branch
+    /// malleability (CVE-2012-2459) is detected -- see `pad_level`.
+    pub fn calculate_merkle_root(transactions: &[VerifiedTransaction]) -> Option<String> {
         if transactions.is_empty() {
             return None;
         }
@@ -168,28 +491,177 @@ impl Block {
         let mut hashes: Vec<Vec<u8>> = transactions.iter().map(|tx| tx.calculate_hash()).collect();
 
         while hashes.len() > 1 {
-            if hashes.len() % 2 != 0 {
-                hashes.push(hashes.last().unwrap().clone());
-            }
-
-            hashes = hashes.chunks(2).map(|chunk| {
-                let mut hasher = Sha256::new();
-                hasher.update(&chunk[0]);
-                hasher.update(&chunk[1]);
-                hasher.finalize().to_vec()
-            }).collect();
+            hashes = fold_level(&pad_level(&hashes)?);
         }
 
         Some(format!("{:x}", sha2::digest::generic_array::GenericArray::from_slice(&hashes[0])))
     }
+
+    /// Builds an inclusion proof for the transaction at `tx_index`: the
+    /// ordered list of sibling hashes from its leaf up to `self.merkle_root`,
+    /// each tagged with which side of the pair it sits on. Mirrors
+    /// `calculate_merkle_root`'s odd-row duplication exactly, so a
+    /// `tx_index` that lands on the final node of an odd-length level
+    /// records that node as its own sibling. Returns `None` for an
+    /// out-of-range index, or for the same malleable trees
+    /// `calculate_merkle_root` rejects.
+    pub fn merkle_proof(&self, tx_index: usize) -> Option<MerkleProof> {
+        if tx_index >= self.transactions.len() {
+            return None;
+        }
+
+        let mut hashes: Vec<Vec<u8>> = self.transactions.iter().map(|tx| tx.calculate_hash()).collect();
+        let mut index = tx_index;
+        let mut steps = Vec::new();
+
+        while hashes.len() > 1 {
+            let padded = pad_level(&hashes)?;
+            let (sibling_index, side) = if index % 2 == 0 {
+                (index + 1, MerkleSide::Right)
+            } else {
+                (index - 1, MerkleSide::Left)
+            };
+            steps.push(MerkleProofStep { sibling_hash: padded[sibling_index].clone(), side });
+
+            hashes = fold_level(&padded);
+            index /= 2;
+        }
+
+        Some(MerkleProof { steps })
+    }
+}
+
+/// Pads `hashes` to even length by duplicating the last element, matching
+/// `calculate_merkle_root`'s original odd-row rule. Returns `None` (instead
+/// of padding) if this level's length was already even and its genuine last
+/// pair is already identical: that pair is indistinguishable from what
+/// self-duplicating a one-shorter, odd-length level would have produced, so
+/// an attacker could add or drop that trailing duplicate transaction without
+/// changing the Merkle root (CVE-2012-2459). Self-duplication of a lone
+/// final node (the normal odd-row case) is not flagged -- only a *real*,
+/// already-duplicate trailing pair is.
+fn pad_level(hashes: &[Vec<u8>]) -> Option<Vec<Vec<u8>>> {
+    let mut padded = hashes.to_vec();
+    let n = padded.len();
+    if n >= 2 && n % 2 == 0 && padded[n - 1] == padded[n - 2] {
+        return None;
+    }
+    if n % 2 != 0 {
+        padded.push(padded.last().unwrap().clone());
+    }
+    Some(padded)
+}
+
+/// Folds one (already-even-length) Merkle level into the next by hashing
+/// each adjacent pair, in the same left-then-right order
+/// `calculate_merkle_root` and `MerkleProof::verify` both rely on.
+fn fold_level(padded: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    padded.chunks(2).map(|chunk| {
+        let mut hasher = Sha256::new();
+        hasher.update(&chunk[0]);
+        hasher.update(&chunk[1]);
+        hasher.finalize().to_vec()
+    }).collect()
+}
+
+/// Which side of a hashed pair a `MerkleProofStep`'s sibling sits on.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerkleSide {
+    Left,
+    Right,
+}
+
+/// One step of a `MerkleProof`: the sibling hash encountered at a given
+/// level, and which side of the pair it sits on.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MerkleProofStep {
+    pub sibling_hash: Vec<u8>,
+    pub side: MerkleSide,
+}
+
+/// An SPV-style inclusion proof: the ordered path of sibling hashes from a
+/// transaction's leaf to a block's Merkle root, produced by
+/// `Block::merkle_proof` and checked independently of the full block by
+/// `verify`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MerkleProof {
+    pub steps: Vec<MerkleProofStep>,
+}
+
+impl MerkleProof {
+    /// Re-hashes `leaf_hash` with each recorded sibling in order and checks
+    /// the result equals `root` (hex-encoded, as returned by
+    /// `Block::calculate_merkle_root`).
+    pub fn verify(&self, leaf_hash: &[u8], root: &str) -> bool {
+        let mut current = leaf_hash.to_vec();
+        for step in &self.steps {
+            let mut hasher = Sha256::new();
+            match step.side {
+                MerkleSide::Left => {
+                    hasher.update(&step.sibling_hash);
+                    hasher.update(&current);
+                }
+                MerkleSide::Right => {
+                    hasher.update(&current);
+                    hasher.update(&step.sibling_hash);
+                }
+            }
+            current = hasher.finalize().to_vec();
+        }
+        format!("{:x}", sha2::digest::generic_array::GenericArray::from_slice(&current)) == root
+    }
 }
 
 
+/// The route `try_import_block` took onto the canonical chain: which block
+/// hashes were rolled back (`retracted`) and which took their place
+/// (`enacted`), both in fork-point-to-tip order. Mirrors the enacted/
+/// retracted tree-route model full Ethereum clients report on a reorg.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportRoute {
+    /// Hashes now on the canonical chain, oldest first, ending with the
+    /// imported block. A single-element list means the import extended the
+    /// tip directly with no reorg.
+    pub enacted: Vec<String>,
+    /// Hashes rolled back off the canonical chain because a competing
+    /// branch overtook it, oldest first. Empty unless a reorg occurred.
+    pub retracted: Vec<String>,
+}
+
 /// Represents the Trust Fabric blockchain.
-#[derive(Debug)]
 pub struct Blockchain {
     pub chain: Vec<Block>,
-    pub pending_transactions: Vec<Transaction>,
+    pub pending_transactions: Vec<VerifiedTransaction>,
+    /// Optional L1 checkpoint client. When set, `anchor_latest_block` can
+    /// commit `mine_and_add_block` output to the configured `Anchor`
+    /// contract; left `None` for nodes that don't run with L1 anchoring.
+    anchor_client: Option<AnchorClient>,
+    /// Optional signing committee. When set, `is_chain_valid` requires every
+    /// non-genesis block to carry at least `committee.threshold` valid
+    /// partial signatures from distinct registered participants.
+    committee: Option<Committee>,
+    /// The initial Dilithium public key (bytes) registered per identity,
+    /// before any in-chain `KeyRotation`. `is_chain_valid` replays
+    /// `KeyRotation` transactions from this starting point to build the
+    /// key timeline it validates each transaction against.
+    genesis_keys: HashMap<String, Vec<u8>>,
+    /// Blocks not on the canonical `chain`, keyed by their own hash --
+    /// competing blocks that extend some ancestor earlier than the current
+    /// tip. `try_import_block` walks a candidate tip's `previous_hash`
+    /// pointers back through this pool to find where it forks from `chain`.
+    side_blocks: HashMap<String, Block>,
+}
+
+impl Debug for Blockchain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Blockchain")
+            .field("chain", &self.chain)
+            .field("pending_transactions", &self.pending_transactions)
+            .field("anchor_client", &self.anchor_client.is_some())
+            .field("committee", &self.committee)
+            .field("side_blocks", &self.side_blocks)
+            .finish()
+    }
 }
 
 impl Blockchain {
@@ -198,21 +670,161 @@ impl Blockchain {
         let mut chain = Blockchain {
             chain: Vec::new(),
             pending_transactions: Vec::new(),
+            anchor_client: None,
+            committee: None,
+            genesis_keys: HashMap::new(),
+            side_blocks: HashMap::new(),
         };
         chain.chain.push(Block::new_genesis());
         chain
     }
 
+    /// Attaches an L1 anchoring client, enabling `anchor_latest_block` and
+    /// `verify_against_anchor`.
+    pub fn with_anchor_client(mut self, anchor_client: AnchorClient) -> Self {
+        self.anchor_client = Some(anchor_client);
+        self
+    }
+
+    /// Attaches a signing committee, enabling threshold-signature
+    /// enforcement in `is_chain_valid`.
+    pub fn with_committee(mut self, committee: Committee) -> Self {
+        self.committee = Some(committee);
+        self
+    }
+
+    /// Registers `identity`'s initial Dilithium public key, establishing
+    /// the start of its key timeline for `is_chain_valid` and `rotate_key`.
+    pub fn with_registered_key(mut self, identity: String, public_key: &PublicKey) -> Self {
+        self.genesis_keys.insert(identity, public_key.clone().into_vec());
+        self
+    }
+
+    /// Replays every mined `KeyRotation` transaction from `genesis_keys`,
+    /// returning the currently active public key (bytes) per identity.
+    /// Used both by `is_chain_valid` (which additionally verifies each
+    /// rotation and transaction as it walks) and by `rotate_key` (which
+    /// only needs to know the current key to sign over).
+    fn key_timeline(&self) -> HashMap<String, Vec<u8>> {
+        let mut active_keys = self.genesis_keys.clone();
+        for block in &self.chain {
+            for tx in &block.transactions {
+                if let Some(rotation) = &tx.key_rotation {
+                    active_keys.insert(tx.from_address.clone(), rotation.new_pubkey.clone());
+                }
+            }
+        }
+        active_keys
+    }
+
+    /// Retires `identity`'s current key in favor of `new_pk`, proving
+    /// continuity of control by signing `new_pk` with `old_sk`. Queues the
+    /// resulting `KeyRotation` transaction in `pending_transactions`;
+    /// blocks signed before it remains validates against the old key.
+    pub fn rotate_key(&mut self, identity: &str, old_sk: &SecretKey, new_pk: &PublicKey) -> Result<()> {
+        let sig_alg = Sig::new(PQC_SIGNATURE_ALGORITHM).map_err(|e| TrustFabricError::PqcAlgorithmCreation(e.to_string()))?;
+        let old_pubkey = self
+            .key_timeline()
+            .get(identity)
+            .cloned()
+            .ok_or_else(|| TrustFabricError::InvalidTransaction(format!("no registered key for identity '{identity}'")))?;
+        let new_pubkey = new_pk.clone().into_vec();
+        let rotation_signature = sig_alg
+            .sign(&new_pubkey, old_sk)
+            .map_err(|e| TrustFabricError::TransactionSigning(e.to_string()))?
+            .into_vec();
+        let recent_blockhash = self.get_last_block().hash.clone();
+
+        self.pending_transactions.push(VerifiedTransaction::assume_verified(UnverifiedTransaction::new_key_rotation(
+            identity.to_string(),
+            old_pubkey,
+            new_pubkey,
+            rotation_signature,
+            recent_blockhash,
+        )));
+        Ok(())
+    }
+
+    /// Commits the latest block's index, Merkle root, and hash to the
+    /// configured L1 anchor contract. Intended to be called after each
+    /// `mine_and_add_block`.
+    pub async fn anchor_latest_block(&self) -> Result<(), AnchoringError> {
+        let anchor_client = self
+            .anchor_client
+            .as_ref()
+            .ok_or_else(|| AnchoringError::AnchorSubmission("no anchor client configured".to_string()))?;
+        let last_block = self.get_last_block();
+        anchor_client
+            .anchor_block(last_block.index, &last_block.merkle_root, &last_block.hash)
+            .await
+    }
+
+    /// Re-reads the Merkle root stored on L1 for the block at `index` and
+    /// compares it against the local chain, giving an external,
+    /// tamper-evident check independent of our own PoW.
+    pub async fn verify_against_anchor(&self, index: u64) -> Result<(), AnchoringError> {
+        let anchor_client = self
+            .anchor_client
+            .as_ref()
+            .ok_or_else(|| AnchoringError::AnchorSubmission("no anchor client configured".to_string()))?;
+        let local_block = self
+            .chain
+            .iter()
+            .find(|b| b.index == index)
+            .ok_or_else(|| AnchoringError::CheckpointRead(index, "index not present in local chain".to_string()))?;
+
+        let anchored_root = anchor_client.read_checkpoint_root(index).await?;
+        if anchored_root != local_block.merkle_root {
+            return Err(AnchoringError::AnchorMismatch(index));
+        }
+        Ok(())
+    }
+
     /// Returns the last block in the chain.
     pub fn get_last_block(&self) -> &Block {
         self.chain.last().unwrap()
     }
 
-    /// Adds a new transaction to the pending pool after validation.
-    pub fn add_transaction(&mut self, transaction: Transaction) {
-        // In a real system, we'd validate the transaction here (e.g., check sender balance).
-        // For this example, we assume signature validation is sufficient.
+    /// Adds a transaction to the pending pool. `transaction` having type
+    /// `VerifiedTransaction` means it already passed
+    /// `UnverifiedTransaction::verify` -- the compiler rules out an unsigned
+    /// or unchecked transaction reaching this point.
+    ///
+    /// Rejects `transaction` if its `recent_blockhash` isn't among the
+    /// trailing `RECENT_BLOCKHASH_WINDOW` canonical block hashes (it's
+    /// either forged or has gone stale), or if a transaction with the same
+    /// hash was already mined within that window -- this doubles as
+    /// double-submit protection and automatic mempool expiry without a
+    /// global account-nonce registry.
+    pub fn add_transaction(&mut self, transaction: VerifiedTransaction) -> Result<()> {
+        let recent_blocks = self.chain.iter().rev().take(RECENT_BLOCKHASH_WINDOW);
+        let mut hash_is_recent = false;
+        let mut already_mined = false;
+        let tx_hash = transaction.calculate_hash();
+        for block in recent_blocks {
+            if block.hash == transaction.recent_blockhash {
+                hash_is_recent = true;
+            }
+            if block.transactions.iter().any(|tx| tx.calculate_hash() == tx_hash) {
+                already_mined = true;
+            }
+        }
+
+        if !hash_is_recent {
+            return Err(TrustFabricError::InvalidTransaction(
+                "transaction's recent_blockhash is not within the recent window".to_string(),
+            )
+            .into());
+        }
+        if already_mined {
+            return Err(TrustFabricError::InvalidTransaction(
+                "transaction was already mined within the recent window".to_string(),
+            )
+            .into());
+        }
+
         self.pending_transactions.push(transaction);
+        Ok(())
     }
     
     /// Mines a new block with pending transactions and adds it to the chain.
@@ -222,10 +834,12 @@ impl Blockchain {
         }
 
         let last_block = self.get_last_block();
+        let difficulty = Self::retarget_difficulty(&self.chain);
         let new_block = Block::new(
             last_block.index + 1,
             last_block.hash.clone(),
             self.pending_transactions.clone(),
+            difficulty,
         );
 
         self.pending_transactions.clear();
@@ -233,8 +847,55 @@ impl Blockchain {
         Ok(new_block)
     }
 
+    /// Picks the difficulty the block extending `history` should carry.
+    /// Holds at `history`'s last difficulty until `RETARGET_WINDOW` blocks
+    /// have elapsed, then compares the actual time taken to mine the
+    /// trailing `RETARGET_WINDOW` blocks against `BLOCK_TIME_TARGET_SECS`:
+    /// raising difficulty by one if blocks came in under half the target
+    /// (too easy), lowering it by one -- never below `MIN_DIFFICULTY` -- if
+    /// they took over double (too hard), and holding otherwise.
+    pub(crate) fn retarget_difficulty(history: &[Block]) -> usize {
+        let Some(last) = history.last() else {
+            return DIFFICULTY;
+        };
+        if history.len() <= RETARGET_WINDOW {
+            return last.difficulty;
+        }
+        let window_start = &history[history.len() - 1 - RETARGET_WINDOW];
+        let actual = last.timestamp - window_start.timestamp;
+        let expected = RETARGET_WINDOW as i64 * BLOCK_TIME_TARGET_SECS;
+        if actual < expected / 2 {
+            last.difficulty + 1
+        } else if actual > expected * 2 {
+            last.difficulty.saturating_sub(1).max(MIN_DIFFICULTY)
+        } else {
+            last.difficulty
+        }
+    }
+
+    /// Applies `retarget_difficulty` to each block of `branch` in turn, as
+    /// if it were appended one at a time after `ancestor_history`. Used by
+    /// `try_import_block` to validate a side branch's claimed difficulties
+    /// before it's allowed to take over the canonical chain.
+    fn validate_branch_difficulties(ancestor_history: &[Block], branch: &[Block]) -> bool {
+        let mut history: Vec<Block> = ancestor_history.to_vec();
+        for block in branch {
+            if block.difficulty != Self::retarget_difficulty(&history) {
+                return false;
+            }
+            history.push(block.clone());
+        }
+        true
+    }
+
     /// Validates the integrity of the entire blockchain.
     pub fn is_chain_valid(&self) -> bool {
+        let sig_alg = match Sig::new(PQC_SIGNATURE_ALGORITHM) {
+            Ok(alg) => alg,
+            Err(_) => return false,
+        };
+        let mut active_keys = self.genesis_keys.clone();
+
         for i in 1..self.chain.len() {
             let current_block = &self.chain[i];
             let previous_block = &self.chain[i - 1];
@@ -253,9 +914,153 @@ impl Blockchain {
             if Some(current_block.merkle_root.clone()) != Block::calculate_merkle_root(&current_block.transactions) {
                 return false;
             }
+
+            // 3b. Check the block's claimed difficulty against the retarget rule.
+            if current_block.difficulty != Self::retarget_difficulty(&self.chain[..i]) {
+                return false;
+            }
+
+            // 4. If a signing committee is configured, require a valid
+            // threshold of distinct, registered partial signatures.
+            if let Some(committee) = &self.committee {
+                if committee
+                    .verify_partials(&current_block.signing_payload(), &current_block.signatures)
+                    .is_err()
+                {
+                    return false;
+                }
+            }
+
+            // 5. Walk the per-identity key timeline: rotations must be
+            // signed by whichever key was active for that identity *before*
+            // the rotation, and transfers must verify against whichever key
+            // was active for their sender at this point in the chain.
+            for tx in &current_block.transactions {
+                if let Some(rotation) = &tx.key_rotation {
+                    let Some(active_pubkey) = active_keys.get(&tx.from_address) else {
+                        return false;
+                    };
+                    if *active_pubkey != rotation.old_pubkey {
+                        return false;
+                    }
+                    let Some(old_pk) = sig_alg.public_key_from_bytes(&rotation.old_pubkey) else {
+                        return false;
+                    };
+                    let Some(rotation_sig) = sig_alg.signature_from_bytes(&rotation.rotation_signature) else {
+                        return false;
+                    };
+                    if sig_alg.verify(&rotation.new_pubkey, &rotation_sig, old_pk).is_err() {
+                        return false;
+                    }
+                    active_keys.insert(tx.from_address.clone(), rotation.new_pubkey.clone());
+                } else if let Some(active_pubkey_bytes) = active_keys.get(&tx.from_address) {
+                    let (Some(active_pk), Some(tx_sig)) = (
+                        sig_alg.public_key_from_bytes(active_pubkey_bytes),
+                        tx.signature.as_deref().and_then(|s| sig_alg.signature_from_bytes(s)),
+                    ) else {
+                        return false;
+                    };
+                    if sig_alg.verify(&tx.signing_payload(), &tx_sig, active_pk).is_err() {
+                        return false;
+                    }
+                }
+            }
         }
         true
     }
+
+    /// Walks backward from `tip` through `previous_hash` pointers -- first
+    /// consulting `side_blocks`, then `chain` -- until it lands on a block
+    /// already on the canonical chain. Returns that ancestor's index in
+    /// `chain` plus the branch's blocks in root-to-tip order (`tip` last),
+    /// or `None` if the branch doesn't (yet) connect back to `chain`, i.e.
+    /// an orphan still waiting on a missing ancestor.
+    fn resolve_branch(&self, tip: Block) -> Option<(usize, Vec<Block>)> {
+        let mut branch = vec![tip];
+        loop {
+            let parent_hash = branch.last().unwrap().previous_hash.clone();
+            if let Some(index) = self.chain.iter().position(|b| b.hash == parent_hash) {
+                branch.reverse();
+                return Some((index, branch));
+            }
+            match self.side_blocks.get(&parent_hash) {
+                Some(parent) => branch.push(parent.clone()),
+                None => return None,
+            }
+        }
+    }
+
+    /// Attempts to import a block received from a peer (or any block not
+    /// produced by this node's own `mine_and_add_block`). A block that
+    /// extends the current tip is appended directly. A block that extends
+    /// an earlier ancestor is held as a side branch; if that branch's
+    /// length overtakes the canonical chain from their common ancestor, a
+    /// reorg is performed: the shorter branch is retracted and the longer
+    /// one enacted in its place, and any retracted transaction not also
+    /// present in an enacted block is returned to `pending_transactions`.
+    pub fn try_import_block(&mut self, block: Block) -> Result<ImportRoute> {
+        if block.hash != block.calculate_hash() {
+            return Err(TrustFabricError::InvalidTransaction("block hash does not match its contents".to_string()).into());
+        }
+        if !block.hash.starts_with(&"0".repeat(block.difficulty)) {
+            return Err(TrustFabricError::InvalidTransaction("block does not meet its claimed proof-of-work difficulty".to_string()).into());
+        }
+        if Some(block.merkle_root.clone()) != Block::calculate_merkle_root(&block.transactions) {
+            return Err(TrustFabricError::InvalidTransaction("block merkle root does not match its transactions".to_string()).into());
+        }
+        if self.chain.iter().any(|b| b.hash == block.hash) || self.side_blocks.contains_key(&block.hash) {
+            return Err(TrustFabricError::InvalidTransaction("block already imported".to_string()).into());
+        }
+
+        if block.previous_hash == self.get_last_block().hash {
+            if block.difficulty != Self::retarget_difficulty(&self.chain) {
+                return Err(TrustFabricError::InvalidTransaction("block difficulty does not match the retarget rule".to_string()).into());
+            }
+            let hash = block.hash.clone();
+            self.chain.push(block);
+            return Ok(ImportRoute { enacted: vec![hash], retracted: Vec::new() });
+        }
+
+        self.side_blocks.insert(block.hash.clone(), block.clone());
+        let Some((ancestor_index, branch)) = self.resolve_branch(block) else {
+            // An orphan: buffered in `side_blocks` until an import connects it.
+            return Ok(ImportRoute::default());
+        };
+
+        let canonical_len = self.chain.len() - 1 - ancestor_index;
+        if branch.len() <= canonical_len {
+            // The competing branch hasn't overtaken the canonical chain yet.
+            return Ok(ImportRoute::default());
+        }
+
+        if !Self::validate_branch_difficulties(&self.chain[..=ancestor_index], &branch) {
+            for b in &branch {
+                self.side_blocks.remove(&b.hash);
+            }
+            return Err(TrustFabricError::InvalidTransaction("competing branch's difficulties do not match the retarget rule".to_string()).into());
+        }
+
+        let retracted_blocks: Vec<Block> = self.chain.drain(ancestor_index + 1..).collect();
+        let retracted: Vec<String> = retracted_blocks.iter().map(|b| b.hash.clone()).collect();
+
+        let enacted: Vec<String> = branch.iter().map(|b| b.hash.clone()).collect();
+        for b in &branch {
+            self.side_blocks.remove(&b.hash);
+        }
+        self.chain.extend(branch.iter().cloned());
+
+        let enacted_tx_hashes: std::collections::HashSet<Vec<u8>> =
+            branch.iter().flat_map(|b| b.transactions.iter().map(|tx| tx.calculate_hash())).collect();
+        for retracted_block in retracted_blocks {
+            for tx in retracted_block.transactions {
+                if !enacted_tx_hashes.contains(&tx.calculate_hash()) {
+                    self.pending_transactions.push(tx);
+                }
+            }
+        }
+
+        Ok(ImportRoute { enacted, retracted })
+    }
 }
 
 impl Default for Blockchain {
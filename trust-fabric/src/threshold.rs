@@ -0,0 +1,152 @@
+// src/threshold.rs
+// ==============================================================================
+// OMEGA PLATFORM - TRUST FABRIC THRESHOLD SIGNING
+// ==============================================================================
+//
+// Implements a t-of-n threshold signing protocol so that blocks (and, on the
+// sentinel side, alerts) require agreement from a quorum of sentinels rather
+// than trusting a single Dilithium key. A `Coordinator` collects per-participant
+// partial signatures over a canonical payload and finalizes once `threshold`
+// distinct, valid partials from the registered `Committee` have been seen.
+//
+
+use oqs::sig::{self, PublicKey, Sig};
+use thiserror::Error;
+
+use crate::PQC_SIGNATURE_ALGORITHM;
+
+#[derive(Error, Debug)]
+pub enum ThresholdError {
+    #[error("participant index {0} is not a member of the committee")]
+    UnknownParticipant(u32),
+    #[error("partial signature from participant {0} failed verification")]
+    InvalidPartial(u32),
+    #[error("duplicate partial signature from participant {0}")]
+    DuplicateParticipant(u32),
+    #[error("only {have} of {threshold} required partials have been collected")]
+    BelowThreshold { have: usize, threshold: usize },
+    #[error("failed to create PQC signature algorithm: {0}")]
+    PqcAlgorithmCreation(String),
+}
+
+/// The registered set of participants eligible to co-sign, and the number of
+/// distinct, valid partials required to finalize a signature.
+#[derive(Clone, Debug)]
+pub struct Committee {
+    pub threshold: u32,
+    pub public_keys: Vec<PublicKey>,
+}
+
+impl Committee {
+    pub fn new(threshold: u32, public_keys: Vec<PublicKey>) -> Self {
+        Committee { threshold, public_keys }
+    }
+
+    /// Verifies that `partials` contains at least `threshold` distinct,
+    /// validly-signed entries over `payload`, each from a registered
+    /// participant index. Rejects duplicate indices and partials whose
+    /// index isn't in the committee.
+    pub fn verify_partials(&self, payload: &[u8], partials: &[(u32, Vec<u8>)]) -> Result<(), ThresholdError> {
+        let sig_alg = Sig::new(PQC_SIGNATURE_ALGORITHM)
+            .map_err(|e| ThresholdError::PqcAlgorithmCreation(e.to_string()))?;
+
+        let mut seen = std::collections::HashSet::new();
+        let mut valid = 0usize;
+
+        for (index, signature_bytes) in partials {
+            if !seen.insert(*index) {
+                return Err(ThresholdError::DuplicateParticipant(*index));
+            }
+            let public_key = self
+                .public_keys
+                .get(*index as usize)
+                .ok_or(ThresholdError::UnknownParticipant(*index))?;
+            let signature = sig_alg
+                .signature_from_bytes(signature_bytes)
+                .ok_or(ThresholdError::InvalidPartial(*index))?;
+            sig_alg
+                .verify(payload, &signature, public_key)
+                .map_err(|_| ThresholdError::InvalidPartial(*index))?;
+            valid += 1;
+        }
+
+        if valid < self.threshold as usize {
+            return Err(ThresholdError::BelowThreshold {
+                have: valid,
+                threshold: self.threshold as usize,
+            });
+        }
+        Ok(())
+    }
+
+    /// A bitmap with bit `i` set for each signer index present in `partials`.
+    pub fn signer_bitmap(partials: &[(u32, Vec<u8>)]) -> u64 {
+        partials.iter().fold(0u64, |bitmap, (index, _)| bitmap | (1u64 << index))
+    }
+}
+
+/// A single sentinel's share of the committee's signing key material. Signs
+/// whatever canonical payload the coordinator presents (a block header or an
+/// alert's canonical fields) and returns its indexed partial.
+pub struct Participant {
+    pub index: u32,
+    pub secret_key: sig::SecretKey,
+}
+
+impl Participant {
+    pub fn new(index: u32, secret_key: sig::SecretKey) -> Self {
+        Participant { index, secret_key }
+    }
+
+    pub fn sign_partial(&self, payload: &[u8]) -> Result<(u32, Vec<u8>), ThresholdError> {
+        let sig_alg = Sig::new(PQC_SIGNATURE_ALGORITHM)
+            .map_err(|e| ThresholdError::PqcAlgorithmCreation(e.to_string()))?;
+        let signature = sig_alg
+            .sign(payload, &self.secret_key)
+            .map_err(|_| ThresholdError::InvalidPartial(self.index))?;
+        Ok((self.index, signature.into_vec()))
+    }
+}
+
+/// Drives collection of partial signatures for a single payload (one block
+/// or one alert) until the committee's threshold is met.
+pub struct Coordinator {
+    committee: Committee,
+    payload: Vec<u8>,
+    partials: Vec<(u32, Vec<u8>)>,
+}
+
+impl Coordinator {
+    pub fn new(committee: Committee, payload: Vec<u8>) -> Self {
+        Coordinator {
+            committee,
+            payload,
+            partials: Vec::new(),
+        }
+    }
+
+    /// Validates and records a partial received over the gRPC channel.
+    /// Rejects partials from unregistered indices, duplicate indices, and
+    /// partials that fail to verify against the committee's public key set.
+    pub fn submit_partial(&mut self, index: u32, signature: Vec<u8>) -> Result<(), ThresholdError> {
+        if self.partials.iter().any(|(i, _)| *i == index) {
+            return Err(ThresholdError::DuplicateParticipant(index));
+        }
+        self.committee
+            .verify_partials(&self.payload, &[(index, signature.clone())])?;
+        self.partials.push((index, signature));
+        Ok(())
+    }
+
+    /// Returns the aggregated `(index, signature)` partials once at least
+    /// `threshold` distinct valid ones have been collected.
+    pub fn try_finalize(&self) -> Result<Vec<(u32, Vec<u8>)>, ThresholdError> {
+        if self.partials.len() < self.committee.threshold as usize {
+            return Err(ThresholdError::BelowThreshold {
+                have: self.partials.len(),
+                threshold: self.committee.threshold as usize,
+            });
+        }
+        Ok(self.partials.clone())
+    }
+}
@@ -0,0 +1,135 @@
+// src/alerting.rs
+// ==============================================================================
+// OMEGA PLATFORM - WASM DYNAMIC RULE MODULE: PLUGGABLE ALERT ROUTING
+// ==============================================================================
+//
+// Every alert used to go through the single host import `trigger_alert`,
+// hardcoding both the delivery channel and a pre-formatted summary string.
+// `AlertMethod` lets a rule pick its delivery channel instead, and
+// `AlertContent` parses a `{field}`-templated string into literal/variable
+// tokens once, rendering it against the triggering event (plus state and
+// the firing rule's severity) each time the rule fires.
+//
+
+use serde::{Deserialize, Serialize};
+
+/// Where a fired alert is delivered. `Log` reproduces the module's original
+/// behavior, routed through `trigger_alert`; the others are handed to the
+/// host via `dispatch_alert`, which owns the actual transport.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertMethod {
+    #[default]
+    Log,
+    Webhook,
+    Email,
+    Metric,
+}
+
+impl AlertMethod {
+    /// The string passed as `dispatch_alert`'s `method` argument.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertMethod::Log => "log",
+            AlertMethod::Webhook => "webhook",
+            AlertMethod::Email => "email",
+            AlertMethod::Metric => "metric",
+        }
+    }
+}
+
+/// The JSON body handed to the host's `dispatch_alert`.
+#[derive(Serialize, Debug)]
+pub struct AlertPayload<'a> {
+    pub metric_name: &'a str,
+    pub source_id: &'a str,
+    pub severity: u8,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ContentToken {
+    Literal(String),
+    /// A dotted field path (e.g. `value.percent` -> `["value", "percent"]`)
+    /// plus the raw placeholder text, re-emitted verbatim if the path
+    /// doesn't resolve.
+    Variable(Vec<String>, String),
+}
+
+/// A `{field}`-templated alert body, parsed once into literal/variable
+/// tokens so repeated firings don't re-scan the template string. Supports
+/// `{metric_name}`, `{source_id}`, `{severity}`, `{host_time}`, and any
+/// dotted path into the event/state context, e.g. `{value.percent}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertContent {
+    tokens: Vec<ContentToken>,
+}
+
+impl AlertContent {
+    /// Parses `template`, splitting it into literal runs and `{dotted.path}`
+    /// variable references.
+    pub fn parse(template: &str) -> Self {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+
+        while let Some(open) = rest.find('{') {
+            literal.push_str(&rest[..open]);
+            let Some(close_rel) = rest[open..].find('}') else {
+                // Unterminated placeholder: treat the remainder as literal text.
+                literal.push_str(&rest[open..]);
+                rest = "";
+                break;
+            };
+            let close = open + close_rel;
+
+            if !literal.is_empty() {
+                tokens.push(ContentToken::Literal(std::mem::take(&mut literal)));
+            }
+            let field = &rest[open + 1..close];
+            let path = field.split('.').map(str::to_string).collect();
+            tokens.push(ContentToken::Variable(path, field.to_string()));
+            rest = &rest[close + 1..];
+        }
+
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            tokens.push(ContentToken::Literal(literal));
+        }
+
+        AlertContent { tokens }
+    }
+
+    /// Renders the template against `ctx`, resolving each variable token by
+    /// walking `ctx` along its dotted path. A placeholder that can't be
+    /// resolved is left in the output as-is.
+    pub fn render(&self, ctx: &serde_json::Value) -> String {
+        let mut output = String::new();
+        for token in &self.tokens {
+            match token {
+                ContentToken::Literal(s) => output.push_str(s),
+                ContentToken::Variable(path, raw) => match resolve(path, ctx) {
+                    serde_json::Value::Null => {
+                        output.push('{');
+                        output.push_str(raw);
+                        output.push('}');
+                    }
+                    serde_json::Value::String(s) => output.push_str(&s),
+                    other => output.push_str(&other.to_string()),
+                },
+            }
+        }
+        output
+    }
+}
+
+fn resolve(path: &[String], ctx: &serde_json::Value) -> serde_json::Value {
+    let mut current = ctx;
+    for segment in path {
+        match current.get(segment) {
+            Some(v) => current = v,
+            None => return serde_json::Value::Null,
+        }
+    }
+    current.clone()
+}
@@ -0,0 +1,57 @@
+// src/anomaly.rs
+// ==============================================================================
+// OMEGA PLATFORM - WASM DYNAMIC RULE MODULE: ADAPTIVE ANOMALY DETECTION
+// ==============================================================================
+//
+// `analyze_network` previously flagged anything more than a fixed
+// `network_spike_factor` times the immediately preceding sample, which is
+// noisy and can't adapt to trends. `EwmaBaseline` instead tracks an
+// exponentially weighted moving mean and variance per (source_id, metric)
+// pair and scores each new sample by how many standard deviations it sits
+// from that baseline, via West's incremental EWMV recurrence. It's generic
+// over any numeric metric, not just network traffic.
+//
+
+use serde::{Deserialize, Serialize};
+
+/// Variance floor so a baseline with zero observed variance doesn't divide
+/// by zero when scoring the next sample.
+const EPSILON: f64 = 1e-6;
+
+/// An exponentially weighted moving mean/variance for one (source_id, metric)
+/// pair.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EwmaBaseline {
+    mean: f64,
+    var: f64,
+    count: u32,
+}
+
+impl EwmaBaseline {
+    /// Scores `x` against the baseline as it stood *before* this
+    /// observation, then folds `x` into the baseline with weight `alpha`.
+    /// Returns the z-score; callers should ignore it until `count()` has
+    /// reached their configured warm-up threshold.
+    pub fn observe(&mut self, x: f64, alpha: f64) -> f64 {
+        if self.count == 0 {
+            self.mean = x;
+            self.var = 0.0;
+            self.count = 1;
+            return 0.0;
+        }
+
+        let z = (x - self.mean) / (self.var + EPSILON).sqrt();
+
+        let diff = x - self.mean;
+        self.mean = alpha * x + (1.0 - alpha) * self.mean;
+        self.var = (1.0 - alpha) * (self.var + alpha * diff * diff);
+        self.count += 1;
+
+        z
+    }
+
+    /// Number of observations folded into this baseline so far.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
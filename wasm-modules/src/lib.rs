@@ -18,6 +18,18 @@ use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use log::{info, warn, error};
 
+mod alerting;
+mod anomaly;
+mod metrics;
+mod rules;
+use alerting::{AlertMethod, AlertPayload};
+use anomaly::EwmaBaseline;
+use metrics::Metrics;
+use rules::Rule;
+
+#[cfg(test)]
+mod lib_test;
+
 // --- 1. Host Function Imports (from the Sentinel Agent) ---
 // This section defines the "safe API" that the host must provide to the WASM module.
 // `wasm-bindgen` will link these to the functions provided by the host environment.
@@ -29,8 +41,14 @@ extern "C" {
 
     // A function to trigger an alert in the Omega Platform.
     // The host (Sentinel Agent) is responsible for implementing this.
+    // This is the default/legacy path, used by rules whose `alert_method` is `Log`.
     fn trigger_alert(severity: u8, summary: &str);
 
+    // Routes an alert through a non-`Log` delivery method. The host decides
+    // the actual transport for `method` (`"webhook"`, `"email"`, `"metric"`);
+    // `payload_json` is an `alerting::AlertPayload` serialized to JSON.
+    fn dispatch_alert(method: &str, payload_json: &str);
+
     // A function to get the current system time (as a Unix timestamp).
     // This is preferred over `std::time` to ensure the WASM module uses the host's clock.
     fn get_host_time() -> f64;
@@ -54,15 +72,51 @@ pub struct AnalysisResult {
     pub severity: Option<u8>,
 }
 
+/// Returned by `on_telemetry_batch`: one `AnalysisResult` per event actually
+/// processed, plus how much of the batch was covered before its deadline.
+#[derive(Serialize, Debug)]
+pub struct BatchResult {
+    pub results: Vec<AnalysisResult>,
+    pub events_processed: usize,
+    /// `true` if the deadline was reached before the whole batch was
+    /// processed; the host should resubmit the remaining events.
+    pub truncated: bool,
+    pub metering: BatchMetering,
+}
+
+/// Per-call metering counters for a single `on_telemetry_batch` invocation,
+/// distinct from the cumulative, cross-call counters in `metrics::Metrics`.
+#[derive(Serialize, Debug, Default)]
+pub struct BatchMetering {
+    pub total: u64,
+    pub by_metric: std::collections::HashMap<String, u64>,
+}
+
 // --- 3. Internal State and Configuration ---
 
 #[derive(Serialize, Deserialize, Debug)]
 struct Config {
     cpu_high_threshold: f64,
     cpu_strike_limit: u32,
-    network_spike_factor: f64,
     alert_cooldown_sec: u64,
     suspicious_processes: Vec<String>,
+    /// EWMA smoothing factor for `EwmaBaseline::observe`; higher weighs
+    /// recent samples more heavily.
+    ewma_alpha: f64,
+    /// How many standard deviations from an `EwmaBaseline` a sample must
+    /// sit at before it's considered anomalous.
+    zscore_threshold: f64,
+    /// Observations a `(source_id, metric)` baseline must accumulate before
+    /// its z-score is trusted enough to alert on, avoiding false positives
+    /// while the baseline is still cold.
+    min_samples: u32,
+    /// Additional detections, expressed as data instead of compiled Rust.
+    /// Evaluated for any event not already handled by the built-in
+    /// `analyze_cpu`/`analyze_network`/`analyze_process` detectors (those
+    /// three remain the default rules for backward compatibility, so an
+    /// empty `rules` list reproduces the module's original behavior).
+    #[serde(default)]
+    rules: Vec<Rule>,
 }
 
 impl Default for Config {
@@ -70,13 +124,16 @@ impl Default for Config {
         Config {
             cpu_high_threshold: 85.0,
             cpu_strike_limit: 3,
-            network_spike_factor: 5.0,
             alert_cooldown_sec: 60,
             suspicious_processes: vec![
                 "powershell.exe".to_string(),
                 "mimikatz.exe".to_string(),
                 "nc.exe".to_string(),
             ],
+            ewma_alpha: 0.2,
+            zscore_threshold: 3.0,
+            min_samples: 20,
+            rules: Vec::new(),
         }
     }
 }
@@ -84,8 +141,14 @@ impl Default for Config {
 #[derive(Serialize, Deserialize, Debug, Default)]
 struct ModuleState {
     cpu_strike_count: u32,
-    previous_network_bytes: std::collections::HashMap<String, u64>,
+    /// Adaptive EWMA baselines keyed by `"{source_id}::{metric}"`, shared by
+    /// any analyzer that wants per-source anomaly scoring (see
+    /// `score_adaptive_metric`).
+    anomaly_baselines: std::collections::HashMap<String, EwmaBaseline>,
     alert_cooldown_timers: std::collections::HashMap<String, f64>,
+    /// Operational counters exported by `collect_metrics`.
+    #[serde(default)]
+    metrics: Metrics,
 }
 
 // Use a Mutex to safely manage global state across potentially concurrent calls.
@@ -100,15 +163,20 @@ static STATE: Mutex<Option<ModuleState>> = Mutex::new(None);
 /// Must be called once by the host before any other functions are used.
 ///
 /// @param config_json: A JSON string representing the `Config` struct.
+/// @param prior_state_json: An optional JSON string previously produced by
+///   `export_state`, for rehydrating a replacement instance across a
+///   zero-downtime module swap instead of starting with fresh state. A
+///   value that fails to parse is logged and discarded in favor of fresh
+///   state, rather than aborting initialization.
 /// @returns A boolean indicating success.
 #[wasm_bindgen]
-pub fn initialize(config_json: &str) -> bool {
+pub fn initialize(config_json: &str, prior_state_json: Option<String>) -> bool {
     // Set up a panic hook to log panics to the console
     std::panic::set_hook(Box::new(console_error_panic_hook::hook));
     wasm_bindgen_console_logger::init().expect("Failed to initialize logger");
 
     info!("[WASM] Initializing module...");
-    
+
     let config: Config = match serde_json::from_str(config_json) {
         Ok(c) => c,
         Err(e) => {
@@ -116,14 +184,58 @@ pub fn initialize(config_json: &str) -> bool {
             return false;
         }
     };
-    
+
+    let state = match prior_state_json {
+        Some(state_json) => match serde_json::from_str(&state_json) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("[WASM] Failed to parse prior state JSON, starting fresh: {}", e);
+                ModuleState::default()
+            }
+        },
+        None => ModuleState::default(),
+    };
+
     *CONFIG.lock().unwrap() = Some(config);
-    *STATE.lock().unwrap() = Some(ModuleState::default());
+    *STATE.lock().unwrap() = Some(state);
 
     info!("[WASM] Module initialized successfully.");
     true
 }
 
+/// Snapshots the live `STATE` so the host can preserve it across a module
+/// reload, e.g. before unloading this instance in favor of an updated
+/// `.wasm` build with new rules. Pass the result to the replacement
+/// instance's `initialize` as `prior_state_json`.
+///
+/// @returns A JSON string representing `ModuleState`, or `"null"` if the
+/// module hasn't been initialized yet.
+#[wasm_bindgen]
+pub fn export_state() -> String {
+    let state_guard = STATE.lock().unwrap();
+    serde_json::to_string(&*state_guard).unwrap()
+}
+
+/// Rehydrates `STATE` from a snapshot produced by `export_state`, without
+/// going through `initialize`. Useful when the host wants to restore state
+/// into an already-initialized instance, e.g. after a failed reload.
+///
+/// @param state_json: A JSON string representing `ModuleState`.
+/// @returns A boolean indicating success; a parse failure leaves the
+///   existing `STATE` untouched.
+#[wasm_bindgen]
+pub fn import_state(state_json: &str) -> bool {
+    let state: ModuleState = match serde_json::from_str(state_json) {
+        Ok(s) => s,
+        Err(e) => {
+            error!("[WASM] Failed to parse state JSON for import: {}", e);
+            return false;
+        }
+    };
+    *STATE.lock().unwrap() = Some(state);
+    true
+}
+
 /// The main entry point for processing telemetry events.
 /// The host calls this function for each new piece of telemetry.
 ///
@@ -151,23 +263,208 @@ pub fn on_telemetry_event(event_json: &str) -> String {
         }).unwrap(),
     };
 
+    serde_json::to_string(&process_event(event_json, state, config)).unwrap()
+}
+
+/// Processes a batch of telemetry events in a single FFI crossing, checking
+/// the host-supplied deadline between events and returning early with a
+/// partial result set if it would be exceeded -- the guest-side analogue of
+/// the fuel/epoch-deadline metering WASM runtimes use to keep untrusted
+/// modules from monopolizing the host.
+///
+/// @param events_json: A JSON array of `TelemetryEvent`s.
+/// @param deadline_ms: A deadline in the same units as `get_host_time()`;
+///   checked before each event, so the call returns at or before it rather
+///   than exactly at it.
+/// @returns A JSON string representing `BatchResult`.
+#[wasm_bindgen]
+pub fn on_telemetry_batch(events_json: &str, deadline_ms: f64) -> String {
+    let mut state_guard = STATE.lock().unwrap();
+    let state = match state_guard.as_mut() {
+        Some(s) => s,
+        None => return serde_json::to_string(&BatchResult {
+            results: Vec::new(),
+            events_processed: 0,
+            truncated: false,
+            metering: BatchMetering::default(),
+        }).unwrap(),
+    };
+
+    let config_guard = CONFIG.lock().unwrap();
+    let config = match config_guard.as_ref() {
+        Some(c) => c,
+        None => return serde_json::to_string(&BatchResult {
+            results: Vec::new(),
+            events_processed: 0,
+            truncated: false,
+            metering: BatchMetering::default(),
+        }).unwrap(),
+    };
+
+    let events: Vec<serde_json::Value> = match serde_json::from_str(events_json) {
+        Ok(e) => e,
+        Err(e) => {
+            state.metrics.record_parse_error();
+            return serde_json::to_string(&BatchResult {
+                results: vec![AnalysisResult {
+                    status: "ERROR".to_string(),
+                    reason: Some(format!("Failed to parse batch JSON: {}", e)),
+                    severity: None,
+                }],
+                events_processed: 0,
+                truncated: false,
+                metering: BatchMetering::default(),
+            }).unwrap();
+        }
+    };
+
+    let mut results = Vec::with_capacity(events.len());
+    let mut metering = BatchMetering::default();
+    let mut truncated = false;
+
+    for event_value in &events {
+        if get_host_time() >= deadline_ms {
+            truncated = true;
+            break;
+        }
+
+        metering.total += 1;
+        if let Some(metric_name) = event_value.get("metric_name").and_then(|v| v.as_str()) {
+            *metering.by_metric.entry(metric_name.to_string()).or_insert(0) += 1;
+        }
+        results.push(process_event(&event_value.to_string(), state, config));
+    }
+
+    serde_json::to_string(&BatchResult {
+        events_processed: results.len(),
+        truncated,
+        results,
+        metering,
+    }).unwrap()
+}
+
+/// The shared per-event pipeline behind `on_telemetry_event` and
+/// `on_telemetry_batch`: parses `event_json`, runs the built-in analyzers,
+/// then falls back to `config.rules` if none of them fired.
+fn process_event(event_json: &str, state: &mut ModuleState, config: &Config) -> AnalysisResult {
     let event: TelemetryEvent = match serde_json::from_str(event_json) {
         Ok(e) => e,
-        Err(e) => return serde_json::to_string(&AnalysisResult {
-            status: "ERROR".to_string(),
-            reason: Some(format!("Failed to parse event JSON: {}", e)),
-            severity: None,
-        }).unwrap(),
+        Err(e) => {
+            state.metrics.record_parse_error();
+            return AnalysisResult {
+                status: "ERROR".to_string(),
+                reason: Some(format!("Failed to parse event JSON: {}", e)),
+                severity: None,
+            };
+        }
     };
 
-    let result = match event.metric_name.as_str() {
+    let metric_name = event.metric_name.clone();
+    let source_id = event.source_id.clone();
+    let value = event.value.clone();
+    state.metrics.record_event(&metric_name);
+
+    let mut result = match event.metric_name.as_str() {
         "cpu_usage" => analyze_cpu(event, state, config),
         "network_traffic" => analyze_network(event, state, config),
         "process_creation" => analyze_process(event, state, config),
         _ => AnalysisResult { status: "NO_HANDLER".to_string(), reason: None, severity: None },
     };
-    
-    serde_json::to_string(&result).unwrap()
+
+    // The built-in analyzers above are the default detections; custom
+    // `config.rules` are evaluated on top of them, and only take effect if
+    // the built-ins didn't already fire for this event.
+    if result.status != "ALERT_TRIGGERED" {
+        let context = serde_json::json!({
+            "metric_name": metric_name,
+            "source_id": source_id,
+            "value": value,
+            "state": state,
+            "config": config,
+        });
+        if let Some(rule_result) = evaluate_custom_rules(&metric_name, &context, config, state) {
+            result = rule_result;
+        }
+    }
+
+    result
+}
+
+/// Exports the module's operational counters for the host to scrape.
+///
+/// @returns A JSON string representing `metrics::MetricsSnapshot`: a
+/// Prometheus text exposition-format body under `prometheus_text`, plus the
+/// same counters as a structured `samples` list for hosts that forward to a
+/// time-series collector instead of scraping text.
+#[wasm_bindgen]
+pub fn collect_metrics() -> String {
+    let state_guard = STATE.lock().unwrap();
+    let snapshot = match state_guard.as_ref() {
+        Some(s) => metrics::snapshot(&s.metrics, s.cpu_strike_count),
+        None => metrics::snapshot(&Metrics::default(), 0),
+    };
+    serde_json::to_string(&snapshot).unwrap()
+}
+
+/// Evaluates `config.rules` in order against `context`, firing an alert and
+/// returning its `AnalysisResult` for the first rule that matches
+/// `metric_name` and whose condition evaluates to `true`. A rule whose
+/// condition fails to parse or evaluate is logged and skipped rather than
+/// aborting the rest of the event pipeline.
+fn evaluate_custom_rules(metric_name: &str, context: &serde_json::Value, config: &Config, state: &mut ModuleState) -> Option<AnalysisResult> {
+    for rule in &config.rules {
+        if !rule.matches_metric(metric_name) {
+            continue;
+        }
+        match rule.evaluate(context) {
+            Ok(true) => {
+                // `severity` and `host_time` are only meaningful once a rule
+                // actually fires, so they're merged in here rather than into
+                // the shared condition-evaluation context.
+                let mut fire_context = context.clone();
+                if let Some(obj) = fire_context.as_object_mut() {
+                    obj.insert("severity".to_string(), serde_json::json!(rule.severity));
+                    obj.insert("host_time".to_string(), serde_json::json!(get_host_time()));
+                }
+
+                let summary = rule.render_summary(&fire_context);
+                dispatch_rule_alert(rule, metric_name, &fire_context, &summary);
+                state.metrics.record_alert(&format!("rule:{metric_name}"));
+                return Some(AnalysisResult {
+                    status: "ALERT_TRIGGERED".to_string(),
+                    reason: Some(summary),
+                    severity: Some(rule.severity),
+                });
+            }
+            Ok(false) => continue,
+            Err(e) => {
+                warn!("[WASM] Rule '{}' failed to evaluate for metric '{}': {}", rule.condition, metric_name, e);
+                continue;
+            }
+        }
+    }
+    None
+}
+
+/// Delivers a fired rule's alert through its configured `alert_method`.
+/// `Log` rules (the default) go through `trigger_alert`, matching the
+/// module's original behavior; every other method renders `content_template`
+/// against `ctx` and hands the result to `dispatch_alert`.
+fn dispatch_rule_alert(rule: &Rule, metric_name: &str, ctx: &serde_json::Value, summary: &str) {
+    match rule.alert_method {
+        AlertMethod::Log => trigger_alert(rule.severity, summary),
+        other => {
+            let source_id = ctx.get("source_id").and_then(|v| v.as_str()).unwrap_or_default();
+            let payload = AlertPayload {
+                metric_name,
+                source_id,
+                severity: rule.severity,
+                content: rule.render_content(ctx),
+            };
+            let payload_json = serde_json::to_string(&payload).unwrap_or_default();
+            dispatch_alert(other.as_str(), &payload_json);
+        }
+    }
 }
 
 // --- 5. Internal Logic Functions ---
@@ -176,11 +473,16 @@ fn analyze_cpu(event: TelemetryEvent, state: &mut ModuleState, config: &Config)
     if let Some(cpu_percent) = event.value.get("percent").and_then(|v| v.as_f64()) {
         if cpu_percent > config.cpu_high_threshold {
             state.cpu_strike_count += 1;
-            if state.cpu_strike_count >= config.cpu_strike_limit && !is_on_cooldown("HIGH_CPU", state) {
-                let summary = format!("Critical: Sustained high CPU usage detected at {:.2}% on node {}", cpu_percent, event.source_id);
-                trigger_alert(8, &summary);
-                start_cooldown("HIGH_CPU", state, config);
-                return AnalysisResult { status: "ALERT_TRIGGERED".to_string(), reason: Some(summary), severity: Some(8) };
+            if state.cpu_strike_count >= config.cpu_strike_limit {
+                if is_on_cooldown("HIGH_CPU", state) {
+                    state.metrics.record_cooldown_suppression("HIGH_CPU");
+                } else {
+                    let summary = format!("Critical: Sustained high CPU usage detected at {:.2}% on node {}", cpu_percent, event.source_id);
+                    trigger_alert(8, &summary);
+                    state.metrics.record_alert("HIGH_CPU");
+                    start_cooldown("HIGH_CPU", state, config);
+                    return AnalysisResult { status: "ALERT_TRIGGERED".to_string(), reason: Some(summary), severity: Some(8) };
+                }
             }
         } else {
             state.cpu_strike_count = 0;
@@ -193,27 +495,50 @@ fn analyze_cpu(event: TelemetryEvent, state: &mut ModuleState, config: &Config)
 
 fn analyze_network(event: TelemetryEvent, state: &mut ModuleState, config: &Config) -> AnalysisResult {
     if let Some(bytes_in) = event.value.get("bytes_in").and_then(|v| v.as_u64()) {
-        if let Some(&previous_bytes) = state.previous_network_bytes.get(&event.source_id) {
-            if bytes_in > previous_bytes * config.network_spike_factor as u64 && !is_on_cooldown("NETWORK_SPIKE", state) {
-                let summary = format!("High severity: Sudden network traffic spike detected on node {}", event.source_id);
-                trigger_alert(7, &summary);
-                start_cooldown("NETWORK_SPIKE", state, config);
-                return AnalysisResult { status: "ALERT_TRIGGERED".to_string(), reason: Some(summary), severity: Some(7) };
+        if let Some(z) = score_adaptive_metric(&event.source_id, "network_traffic.bytes_in", bytes_in as f64, state, config) {
+            if z.abs() > config.zscore_threshold {
+                if is_on_cooldown("NETWORK_SPIKE", state) {
+                    state.metrics.record_cooldown_suppression("NETWORK_SPIKE");
+                } else {
+                    let summary = format!("High severity: Anomalous network traffic (z-score {:.2}) detected on node {}", z, event.source_id);
+                    trigger_alert(7, &summary);
+                    state.metrics.record_alert("NETWORK_SPIKE");
+                    start_cooldown("NETWORK_SPIKE", state, config);
+                    return AnalysisResult { status: "ALERT_TRIGGERED".to_string(), reason: Some(summary), severity: Some(7) };
+                }
             }
         }
-        state.previous_network_bytes.insert(event.source_id, bytes_in);
         AnalysisResult { status: "NORMAL".to_string(), reason: None, severity: None }
     } else {
         AnalysisResult { status: "ERROR".to_string(), reason: Some("Invalid network telemetry format".to_string()), severity: None }
     }
 }
 
+/// Scores `value` against the adaptive EWMA baseline for `(source_id,
+/// metric)`, folding it into that baseline either way. Returns `None`
+/// during the `config.min_samples` warm-up window, when the baseline isn't
+/// yet trustworthy enough to alert on. Shared by any analyzer that wants
+/// per-source statistical anomaly scoring, not just network traffic.
+fn score_adaptive_metric(source_id: &str, metric: &str, value: f64, state: &mut ModuleState, config: &Config) -> Option<f64> {
+    let key = format!("{source_id}::{metric}");
+    let baseline = state.anomaly_baselines.entry(key).or_default();
+    let z = baseline.observe(value, config.ewma_alpha);
+
+    if baseline.count() < config.min_samples {
+        return None;
+    }
+    Some(z)
+}
+
 fn analyze_process(event: TelemetryEvent, state: &mut ModuleState, config: &Config) -> AnalysisResult {
     if let Some(process_name) = event.value.get("process_name").and_then(|v| v.as_str()) {
         if config.suspicious_processes.iter().any(|suspicious| process_name.eq_ignore_ascii_case(suspicious)) {
-            if !is_on_cooldown("SUSPICIOUS_PROCESS", state) {
+            if is_on_cooldown("SUSPICIOUS_PROCESS", state) {
+                state.metrics.record_cooldown_suppression("SUSPICIOUS_PROCESS");
+            } else {
                 let summary = format!("Critical: Suspicious process '{}' executed on node {}", process_name, event.source_id);
                 trigger_alert(9, &summary);
+                state.metrics.record_alert("SUSPICIOUS_PROCESS");
                 start_cooldown("SUSPICIOUS_PROCESS", state, config);
                 return AnalysisResult { status: "ALERT_TRIGGERED".to_string(), reason: Some(summary), severity: Some(9) };
             }
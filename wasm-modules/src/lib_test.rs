@@ -28,9 +28,10 @@ static MOCKED_TIME: Mutex<f64> = Mutex::new(1_000_000.0);
 #[wasm_bindgen(inline_js = r#"
     // This JS code will be executed in the test environment (e.g., headless browser)
     // It provides the implementations for the functions we declared in `extern "C"`.
-    
+
     // We use global variables in JS to store the state of our mocks.
     global.triggered_alerts = [];
+    global.dispatched_alerts = [];
     global.mock_time = 1000000.0;
 
     export function trigger_alert(severity, summary) {
@@ -38,14 +39,20 @@ static MOCKED_TIME: Mutex<f64> = Mutex::new(1_000_000.0);
         global.triggered_alerts.push({ severity, summary });
     }
 
+    export function dispatch_alert(method, payload_json) {
+        console.log(`[JS Mock] dispatch_alert called with method: ${method}, payload: ${payload_json}`);
+        global.dispatched_alerts.push({ method, payload: JSON.parse(payload_json) });
+    }
+
     export function get_host_time() {
         global.mock_time += 10.0; // Increment time on each call to simulate passing time
         return global.mock_time;
     }
-    
+
     // Helper function for tests to reset the mock state
     export function reset_mocks() {
         global.triggered_alerts = [];
+        global.dispatched_alerts = [];
         global.mock_time = 1000000.0;
     }
 
@@ -53,16 +60,34 @@ static MOCKED_TIME: Mutex<f64> = Mutex::new(1_000_000.0);
     export function get_alert_count() {
         return global.triggered_alerts.length;
     }
-    
+
     // Helper function to get the last triggered alert
     export function get_last_alert() {
         return global.triggered_alerts[global.triggered_alerts.length - 1];
     }
+
+    // Helper function to get the number of alerts dispatched via `dispatch_alert`
+    export function get_dispatch_count() {
+        return global.dispatched_alerts.length;
+    }
+
+    // Helper function to get the `method` of the last alert dispatched via `dispatch_alert`
+    export function get_last_dispatch_method() {
+        return global.dispatched_alerts[global.dispatched_alerts.length - 1].method;
+    }
+
+    // Helper function to get the raw payload JSON of the last `dispatch_alert` call
+    export function get_last_dispatch_payload_json() {
+        return JSON.stringify(global.dispatched_alerts[global.dispatched_alerts.length - 1].payload);
+    }
 "#)]
 extern "C" {
     fn reset_mocks();
     fn get_alert_count() -> usize;
     fn get_last_alert() -> JsValue;
+    fn get_dispatch_count() -> usize;
+    fn get_last_dispatch_method() -> String;
+    fn get_last_dispatch_payload_json() -> String;
 }
 
 // --- Test Setup ---
@@ -74,7 +99,7 @@ fn setup() {
     
     // Initialize the module with default config
     let config_json = serde_json::to_string(&Config::default()).unwrap();
-    let success = initialize(&config_json);
+    let success = initialize(&config_json, None);
     assert!(success, "Module should initialize successfully");
 }
 
@@ -92,7 +117,7 @@ fn test_initialization() {
         "cpu_strike_limit": 5,
         "suspicious_processes": ["test.exe"]
     }).to_string();
-    let success = initialize(&custom_config);
+    let success = initialize(&custom_config, None);
     assert!(success);
     let config = CONFIG.lock().unwrap();
     let config_ref = config.as_ref().unwrap();
@@ -183,23 +208,26 @@ fn test_alert_cooldown_mechanism() {
 #[wasm_bindgen_test]
 fn test_network_spike_detection() {
     setup();
-    
-    // First event establishes baseline
-    let event1 = json!({
+
+    // Feed a stable baseline past the `min_samples` warm-up window, so the
+    // adaptive EWMA baseline is trusted before we look for anomalies.
+    let baseline_event = json!({
         "metric_name": "network_traffic",
         "source_id": "test-node-2",
         "value": {"bytes_in": 1000}
     }).to_string();
-    on_telemetry_event(&event1);
-    assert_eq!(get_alert_count(), 0);
+    for _ in 0..Config::default().min_samples {
+        on_telemetry_event(&baseline_event);
+    }
+    assert_eq!(get_alert_count(), 0, "a stable baseline should not alert");
 
-    // Second event is a huge spike (10x > 5x factor)
-    let event2 = json!({
+    // A huge spike relative to the learned baseline should trigger an alert.
+    let spike_event = json!({
         "metric_name": "network_traffic",
         "source_id": "test-node-2",
-        "value": {"bytes_in": 10000}
+        "value": {"bytes_in": 50000}
     }).to_string();
-    let res_json = on_telemetry_event(&event2);
+    let res_json = on_telemetry_event(&spike_event);
     let result: AnalysisResult = serde_json::from_str(&res_json).unwrap();
 
     assert_eq!(result.status, "ALERT_TRIGGERED");
@@ -207,6 +235,32 @@ fn test_network_spike_detection() {
     assert_eq!(get_alert_count(), 1);
 }
 
+#[wasm_bindgen_test]
+fn test_network_baseline_warm_up_suppresses_false_positives() {
+    setup();
+
+    // A spike that arrives before the baseline has accumulated
+    // `min_samples` observations must not alert, even though its z-score
+    // would otherwise clear the threshold.
+    let first_event = json!({
+        "metric_name": "network_traffic",
+        "source_id": "test-node-8",
+        "value": {"bytes_in": 1000}
+    }).to_string();
+    on_telemetry_event(&first_event);
+
+    let spike_event = json!({
+        "metric_name": "network_traffic",
+        "source_id": "test-node-8",
+        "value": {"bytes_in": 50000}
+    }).to_string();
+    let res_json = on_telemetry_event(&spike_event);
+    let result: AnalysisResult = serde_json::from_str(&res_json).unwrap();
+
+    assert_eq!(result.status, "NORMAL", "an anomaly during warm-up should not alert");
+    assert_eq!(get_alert_count(), 0);
+}
+
 #[wasm_bindgen_test]
 fn test_suspicious_process_detection() {
     setup();
@@ -230,7 +284,291 @@ fn test_invalid_event_json() {
     let invalid_event = r#"{"metric_name": "cpu_usage", "source_id": "test-node", "val": {}}"#;
     let res_json = on_telemetry_event(invalid_event);
     let result: AnalysisResult = serde_json::from_str(&res_json).unwrap();
-    
+
     assert_eq!(result.status, "ERROR");
     assert!(result.reason.unwrap().contains("Invalid CPU telemetry format"));
-}
\ No newline at end of file
+}
+
+#[wasm_bindgen_test]
+fn test_custom_rule_fires_alert() {
+    reset_mocks();
+    *CONFIG.lock().unwrap() = None;
+    *STATE.lock().unwrap() = None;
+    let mut config = Config::default();
+    config.rules.push(Rule {
+        metric_name: Some("disk_usage".to_string()),
+        condition: "value.percent_full >= 90".to_string(),
+        severity: 6,
+        summary: "Disk on {source_id} is {value.percent_full}% full".to_string(),
+        alert_method: AlertMethod::Log,
+        content_template: None,
+    });
+    let success = initialize(&serde_json::to_string(&config).unwrap(), None);
+    assert!(success);
+
+    let event = json!({
+        "metric_name": "disk_usage",
+        "source_id": "test-node-4",
+        "value": {"percent_full": 95.0}
+    }).to_string();
+
+    let res_json = on_telemetry_event(&event);
+    let result: AnalysisResult = serde_json::from_str(&res_json).unwrap();
+
+    assert_eq!(result.status, "ALERT_TRIGGERED");
+    assert_eq!(result.severity, Some(6));
+    assert_eq!(result.reason, Some("Disk on test-node-4 is 95.0% full".to_string()));
+    assert_eq!(get_alert_count(), 1);
+}
+
+#[wasm_bindgen_test]
+fn test_custom_rule_with_webhook_method_dispatches_structured_payload() {
+    reset_mocks();
+    *CONFIG.lock().unwrap() = None;
+    *STATE.lock().unwrap() = None;
+    let mut config = Config::default();
+    config.rules.push(Rule {
+        metric_name: Some("disk_usage".to_string()),
+        condition: "value.percent_full >= 90".to_string(),
+        severity: 6,
+        summary: "Disk on {source_id} is {value.percent_full}% full".to_string(),
+        alert_method: AlertMethod::Webhook,
+        content_template: Some("{source_id} hit {value.percent_full}% (severity {severity})".to_string()),
+    });
+    let success = initialize(&serde_json::to_string(&config).unwrap(), None);
+    assert!(success);
+
+    let event = json!({
+        "metric_name": "disk_usage",
+        "source_id": "test-node-4",
+        "value": {"percent_full": 95.0}
+    }).to_string();
+
+    let res_json = on_telemetry_event(&event);
+    let result: AnalysisResult = serde_json::from_str(&res_json).unwrap();
+
+    assert_eq!(result.status, "ALERT_TRIGGERED");
+    assert_eq!(get_alert_count(), 0);
+    assert_eq!(get_dispatch_count(), 1);
+    assert_eq!(get_last_dispatch_method(), "webhook");
+
+    let payload: serde_json::Value = serde_json::from_str(&get_last_dispatch_payload_json()).unwrap();
+    assert_eq!(payload["metric_name"], "disk_usage");
+    assert_eq!(payload["source_id"], "test-node-4");
+    assert_eq!(payload["severity"], 6);
+    assert_eq!(payload["content"], "test-node-4 hit 95.0% (severity 6)");
+}
+
+#[wasm_bindgen_test]
+fn test_custom_rule_does_not_fire_for_other_metrics() {
+    reset_mocks();
+    *CONFIG.lock().unwrap() = None;
+    *STATE.lock().unwrap() = None;
+    let mut config = Config::default();
+    config.rules.push(Rule {
+        metric_name: Some("disk_usage".to_string()),
+        condition: "value.percent_full >= 90".to_string(),
+        severity: 6,
+        summary: "Disk alert".to_string(),
+        alert_method: AlertMethod::Log,
+        content_template: None,
+    });
+    let success = initialize(&serde_json::to_string(&config).unwrap(), None);
+    assert!(success);
+
+    let event = json!({
+        "metric_name": "memory_usage",
+        "source_id": "test-node-5",
+        "value": {"percent_full": 99.0}
+    }).to_string();
+
+    let res_json = on_telemetry_event(&event);
+    let result: AnalysisResult = serde_json::from_str(&res_json).unwrap();
+
+    assert_eq!(result.status, "NO_HANDLER");
+    assert_eq!(get_alert_count(), 0);
+}
+
+#[wasm_bindgen_test]
+fn test_custom_rule_with_logic_and_builtin_call() {
+    reset_mocks();
+    *CONFIG.lock().unwrap() = None;
+    *STATE.lock().unwrap() = None;
+    let mut config = Config::default();
+    config.rules.push(Rule {
+        metric_name: Some("login_attempt".to_string()),
+        condition: "value.failed == true and contains(config.suspicious_processes, value.via)".to_string(),
+        severity: 5,
+        summary: "Suspicious login via {value.via}".to_string(),
+        alert_method: AlertMethod::Log,
+        content_template: None,
+    });
+    let success = initialize(&serde_json::to_string(&config).unwrap(), None);
+    assert!(success);
+
+    let event = json!({
+        "metric_name": "login_attempt",
+        "source_id": "test-node-6",
+        "value": {"failed": true, "via": "mimikatz.exe"}
+    }).to_string();
+
+    let res_json = on_telemetry_event(&event);
+    let result: AnalysisResult = serde_json::from_str(&res_json).unwrap();
+
+    assert_eq!(result.status, "ALERT_TRIGGERED");
+    assert_eq!(result.severity, Some(5));
+    assert_eq!(get_alert_count(), 1);
+}
+
+#[wasm_bindgen_test]
+fn test_invalid_rule_condition_is_skipped_not_fatal() {
+    reset_mocks();
+    *CONFIG.lock().unwrap() = None;
+    *STATE.lock().unwrap() = None;
+    let mut config = Config::default();
+    config.rules.push(Rule {
+        metric_name: Some("disk_usage".to_string()),
+        condition: "value.percent_full >=".to_string(), // malformed
+        severity: 6,
+        summary: "Disk alert".to_string(),
+        alert_method: AlertMethod::Log,
+        content_template: None,
+    });
+    let success = initialize(&serde_json::to_string(&config).unwrap(), None);
+    assert!(success);
+
+    let event = json!({
+        "metric_name": "disk_usage",
+        "source_id": "test-node-7",
+        "value": {"percent_full": 95.0}
+    }).to_string();
+
+    let res_json = on_telemetry_event(&event);
+    let result: AnalysisResult = serde_json::from_str(&res_json).unwrap();
+
+    assert_eq!(result.status, "NO_HANDLER");
+    assert_eq!(get_alert_count(), 0);
+}
+#[wasm_bindgen_test]
+fn test_collect_metrics_tracks_events_alerts_and_suppressions() {
+    reset_mocks();
+    *CONFIG.lock().unwrap() = None;
+    *STATE.lock().unwrap() = None;
+    let config = Config::default();
+    let success = initialize(&serde_json::to_string(&config).unwrap(), None);
+    assert!(success);
+
+    let bad_event = r#"{"metric_name": "cpu_usage", "source_id": "test-node"#; // malformed JSON
+    on_telemetry_event(bad_event);
+
+    let mut cpu_event = |percent: f64| {
+        on_telemetry_event(&json!({
+            "metric_name": "cpu_usage",
+            "source_id": "test-node-9",
+            "value": {"percent": percent}
+        }).to_string())
+    };
+    for _ in 0..config.cpu_strike_limit {
+        cpu_event(95.0);
+    }
+    // Still within the cooldown window, so this strike is suppressed rather than re-alerting.
+    cpu_event(95.0);
+
+    let snapshot: serde_json::Value = serde_json::from_str(&collect_metrics()).unwrap();
+
+    assert!(snapshot["prometheus_text"].as_str().unwrap().contains("omega_cpu_strike_count"));
+    assert_eq!(snapshot["samples"].as_array().unwrap().iter()
+        .find(|s| s["name"] == "omega_events_processed_total" && s["labels"]["metric_name"] == "cpu_usage")
+        .unwrap()["value"], (config.cpu_strike_limit + 1) as f64);
+    assert_eq!(snapshot["samples"].as_array().unwrap().iter()
+        .find(|s| s["name"] == "omega_alerts_triggered_total" && s["labels"]["alert_type"] == "HIGH_CPU")
+        .unwrap()["value"], 1.0);
+    assert_eq!(snapshot["samples"].as_array().unwrap().iter()
+        .find(|s| s["name"] == "omega_cooldown_suppressions_total" && s["labels"]["alert_type"] == "HIGH_CPU")
+        .unwrap()["value"], 1.0);
+    assert_eq!(snapshot["samples"].as_array().unwrap().iter()
+        .find(|s| s["name"] == "omega_parse_errors_total")
+        .unwrap()["value"], 1.0);
+}
+
+#[wasm_bindgen_test]
+fn test_export_import_state_round_trip() {
+    reset_mocks();
+    *CONFIG.lock().unwrap() = None;
+    *STATE.lock().unwrap() = None;
+    let config = Config::default();
+    let success = initialize(&serde_json::to_string(&config).unwrap(), None);
+    assert!(success);
+
+    let high_cpu_event = json!({
+        "metric_name": "cpu_usage",
+        "source_id": "test-node-1",
+        "value": {"percent": 90.0}
+    }).to_string();
+    on_telemetry_event(&high_cpu_event);
+    on_telemetry_event(&high_cpu_event);
+    assert_eq!(STATE.lock().unwrap().as_ref().unwrap().cpu_strike_count, 2);
+
+    let exported = export_state();
+
+    // A fresh instance reload: a new module picks up the prior state via `initialize`.
+    *STATE.lock().unwrap() = None;
+    let success = initialize(&serde_json::to_string(&config).unwrap(), Some(exported.clone()));
+    assert!(success);
+    assert_eq!(STATE.lock().unwrap().as_ref().unwrap().cpu_strike_count, 2);
+
+    // `import_state` restores into an already-initialized instance too.
+    *STATE.lock().unwrap().as_mut().unwrap() = ModuleState::default();
+    assert_eq!(STATE.lock().unwrap().as_ref().unwrap().cpu_strike_count, 0);
+    assert!(import_state(&exported));
+    assert_eq!(STATE.lock().unwrap().as_ref().unwrap().cpu_strike_count, 2);
+
+    // A malformed prior state falls back to fresh rather than failing init.
+    let success = initialize(&serde_json::to_string(&config).unwrap(), Some("not json".to_string()));
+    assert!(success);
+    assert_eq!(STATE.lock().unwrap().as_ref().unwrap().cpu_strike_count, 0);
+}
+
+#[wasm_bindgen_test]
+fn test_on_telemetry_batch_truncates_at_deadline() {
+    reset_mocks();
+    *CONFIG.lock().unwrap() = None;
+    *STATE.lock().unwrap() = None;
+    let config = Config::default();
+    let success = initialize(&serde_json::to_string(&config).unwrap(), None);
+    assert!(success);
+
+    let events = json!([
+        {"metric_name": "cpu_usage", "source_id": "n1", "value": {"percent": 50.0}},
+        {"metric_name": "cpu_usage", "source_id": "n2", "value": {"percent": 50.0}},
+        {"metric_name": "cpu_usage", "source_id": "n3", "value": {"percent": 50.0}}
+    ]).to_string();
+
+    // `get_host_time` advances by 10 per call starting from 1_000_000.0, so
+    // this deadline is crossed on the third event's pre-processing check.
+    let batch_json = on_telemetry_batch(&events, 1_000_025.0);
+    let batch: serde_json::Value = serde_json::from_str(&batch_json).unwrap();
+
+    assert_eq!(batch["events_processed"], 2);
+    assert_eq!(batch["truncated"], true);
+    assert_eq!(batch["results"].as_array().unwrap().len(), 2);
+    assert_eq!(batch["metering"]["total"], 2);
+    assert_eq!(batch["metering"]["by_metric"]["cpu_usage"], 2);
+}
+
+#[wasm_bindgen_test]
+fn test_on_telemetry_batch_reports_parse_errors() {
+    reset_mocks();
+    *CONFIG.lock().unwrap() = None;
+    *STATE.lock().unwrap() = None;
+    let config = Config::default();
+    let success = initialize(&serde_json::to_string(&config).unwrap(), None);
+    assert!(success);
+
+    let batch_json = on_telemetry_batch("not a json array", 2_000_000.0);
+    let batch: serde_json::Value = serde_json::from_str(&batch_json).unwrap();
+
+    assert_eq!(batch["events_processed"], 0);
+    assert_eq!(batch["truncated"], false);
+    assert_eq!(batch["results"][0]["status"], "ERROR");
+}
@@ -0,0 +1,137 @@
+// src/metrics.rs
+// ==============================================================================
+// OMEGA PLATFORM - WASM DYNAMIC RULE MODULE: OPERATIONAL METRICS
+// ==============================================================================
+//
+// Every detection the module makes is otherwise only visible as an opaque
+// `trigger_alert`/`dispatch_alert` side-effect. `Metrics` accumulates
+// monotonic counters (plus one gauge) alongside `ModuleState`, and `snapshot`
+// renders them in Prometheus text exposition format -- plus the same data as
+// a structured sample list, for hosts that forward to a time-series
+// collector instead of scraping text -- for the host to pull via
+// `collect_metrics`.
+//
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Operational counters tracked alongside `ModuleState`. All fields are
+/// monotonic counters except `cpu_strike_count`, which mirrors the current
+/// gauge value from `ModuleState` at snapshot time.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Metrics {
+    /// Telemetry events processed, keyed by `metric_name`.
+    pub events_processed_total: HashMap<String, u64>,
+    /// Alerts triggered, keyed by alert type (`HIGH_CPU`, `NETWORK_SPIKE`,
+    /// `SUSPICIOUS_PROCESS`, or `rule:<metric_name>` for a `config.rules`
+    /// match).
+    pub alerts_triggered_total: HashMap<String, u64>,
+    /// `event_json` that failed to parse in `on_telemetry_event`.
+    pub parse_errors_total: u64,
+    /// Alerts that would have fired but were suppressed by an active
+    /// cooldown, keyed by alert type.
+    pub cooldown_suppressions_total: HashMap<String, u64>,
+}
+
+impl Metrics {
+    pub fn record_event(&mut self, metric_name: &str) {
+        *self.events_processed_total.entry(metric_name.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn record_alert(&mut self, alert_type: &str) {
+        *self.alerts_triggered_total.entry(alert_type.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn record_parse_error(&mut self) {
+        self.parse_errors_total += 1;
+    }
+
+    pub fn record_cooldown_suppression(&mut self, alert_type: &str) {
+        *self.cooldown_suppressions_total.entry(alert_type.to_string()).or_insert(0) += 1;
+    }
+}
+
+/// A single exported sample, for hosts that forward structured metrics to a
+/// time-series collector instead of scraping `prometheus_text`.
+#[derive(Serialize, Debug)]
+pub struct MetricSample {
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+}
+
+/// The payload returned by `collect_metrics`.
+#[derive(Serialize, Debug)]
+pub struct MetricsSnapshot {
+    pub prometheus_text: String,
+    pub samples: Vec<MetricSample>,
+}
+
+fn push_family(
+    text: &mut String,
+    samples: &mut Vec<MetricSample>,
+    name: &str,
+    kind: &str,
+    label_name: &str,
+    values: &HashMap<String, u64>,
+) {
+    text.push_str(&format!("# TYPE {name} {kind}\n"));
+    for (label_value, count) in values {
+        text.push_str(&format!("{name}{{{label_name}=\"{label_value}\"}} {count}\n"));
+        samples.push(MetricSample {
+            name: name.to_string(),
+            labels: BTreeMap::from([(label_name.to_string(), label_value.clone())]),
+            value: *count as f64,
+        });
+    }
+}
+
+/// Renders `metrics` (plus the live `cpu_strike_count` gauge from
+/// `ModuleState`) as a `MetricsSnapshot`.
+pub fn snapshot(metrics: &Metrics, cpu_strike_count: u32) -> MetricsSnapshot {
+    let mut text = String::new();
+    let mut samples = Vec::new();
+
+    push_family(
+        &mut text,
+        &mut samples,
+        "omega_events_processed_total",
+        "counter",
+        "metric_name",
+        &metrics.events_processed_total,
+    );
+    push_family(
+        &mut text,
+        &mut samples,
+        "omega_alerts_triggered_total",
+        "counter",
+        "alert_type",
+        &metrics.alerts_triggered_total,
+    );
+    push_family(
+        &mut text,
+        &mut samples,
+        "omega_cooldown_suppressions_total",
+        "counter",
+        "alert_type",
+        &metrics.cooldown_suppressions_total,
+    );
+
+    text.push_str("# TYPE omega_parse_errors_total counter\n");
+    text.push_str(&format!("omega_parse_errors_total {}\n", metrics.parse_errors_total));
+    samples.push(MetricSample {
+        name: "omega_parse_errors_total".to_string(),
+        labels: BTreeMap::new(),
+        value: metrics.parse_errors_total as f64,
+    });
+
+    text.push_str("# TYPE omega_cpu_strike_count gauge\n");
+    text.push_str(&format!("omega_cpu_strike_count {cpu_strike_count}\n"));
+    samples.push(MetricSample {
+        name: "omega_cpu_strike_count".to_string(),
+        labels: BTreeMap::new(),
+        value: cpu_strike_count as f64,
+    });
+
+    MetricsSnapshot { prometheus_text: text, samples }
+}
@@ -0,0 +1,389 @@
+// src/rules.rs
+// ==============================================================================
+// OMEGA PLATFORM - WASM DYNAMIC RULE MODULE: CONFIG-DRIVEN RULE DSL
+// ==============================================================================
+//
+// Hardcoding every detection in analyze_cpu/analyze_network/analyze_process
+// means shipping a new WASM build for each new detection. `Rule` lets
+// operators describe additional detections as data inside `Config`: a small
+// recursive-descent parser turns a `condition` string into an `Expr` AST
+// once per evaluation, which is then walked against a context built from the
+// telemetry event, the module's state, and its config.
+//
+
+use serde::{Deserialize, Serialize};
+
+use crate::alerting::{AlertContent, AlertMethod};
+
+/// A single data-driven detection rule, evaluated against every telemetry
+/// event whose `metric_name` matches.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Rule {
+    /// Restricts this rule to events with this `metric_name`; `None` matches any event.
+    #[serde(default)]
+    pub metric_name: Option<String>,
+    /// A boolean expression in the rule DSL, e.g. `"value.percent > 90"`.
+    pub condition: String,
+    pub severity: u8,
+    /// Alert summary template; `{field}` placeholders are interpolated from
+    /// the same context the condition is evaluated against.
+    pub summary: String,
+    /// Delivery channel for this rule's alert. Defaults to `Log`, which
+    /// reproduces the original `trigger_alert`-only behavior.
+    #[serde(default)]
+    pub alert_method: AlertMethod,
+    /// Template for the payload handed to non-`Log` delivery methods via
+    /// `dispatch_alert`. Falls back to `summary` when absent.
+    #[serde(default)]
+    pub content_template: Option<String>,
+}
+
+impl Rule {
+    /// Returns `true` if this rule should be evaluated against an event
+    /// named `event_metric_name`.
+    pub fn matches_metric(&self, event_metric_name: &str) -> bool {
+        match &self.metric_name {
+            Some(name) => name == event_metric_name,
+            None => true,
+        }
+    }
+
+    /// Parses and evaluates `self.condition` against `ctx`.
+    pub fn evaluate(&self, ctx: &serde_json::Value) -> Result<bool, String> {
+        let expr = parse(&self.condition)?;
+        match eval(&expr, ctx)? {
+            serde_json::Value::Bool(b) => Ok(b),
+            other => Err(format!("rule condition did not evaluate to a bool: {other}")),
+        }
+    }
+
+    /// Renders `self.summary`, replacing every `{field}` placeholder with
+    /// the value of `field` resolved against `ctx`. A placeholder that
+    /// can't be resolved is left in the output as-is.
+    pub fn render_summary(&self, ctx: &serde_json::Value) -> String {
+        AlertContent::parse(&self.summary).render(ctx)
+    }
+
+    /// Renders `self.content_template` (falling back to `self.summary`)
+    /// against `ctx`, for the payload sent to non-`Log` delivery methods.
+    pub fn render_content(&self, ctx: &serde_json::Value) -> String {
+        let template = self.content_template.as_deref().unwrap_or(&self.summary);
+        AlertContent::parse(template).render(ctx)
+    }
+}
+
+// --- AST ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Gt,
+    Lt,
+    Ge,
+    Le,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogicOp {
+    And,
+    Or,
+    Not,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Literal),
+    /// A dotted field path, e.g. `value.percent` -> `["value", "percent"]`,
+    /// resolved by walking the evaluation context.
+    Field(Vec<String>),
+    Compare { op: CompareOp, lhs: Box<Expr>, rhs: Box<Expr> },
+    /// `rhs` is `None` for `Not`, which is unary.
+    Logic { op: LogicOp, lhs: Box<Expr>, rhs: Option<Box<Expr>> },
+    Call { name: String, args: Vec<Expr> },
+}
+
+// --- Lexer ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn lex(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '(' => { tokens.push(Token::LParen); i += 1; }
+            ')' => { tokens.push(Token::RParen); i += 1; }
+            ',' => { tokens.push(Token::Comma); i += 1; }
+            '\'' | '"' => {
+                let quote = c;
+                let mut s = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != quote {
+                    s.push(chars[i]);
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(format!("unterminated string literal in condition: {source}"));
+                }
+                i += 1; // consume closing quote
+                tokens.push(Token::Str(s));
+            }
+            '>' | '<' | '=' | '!' => {
+                let two_char = chars.get(i + 1) == Some(&'=');
+                let op = match (c, two_char) {
+                    ('>', true) => { i += 2; "Ge" }
+                    ('>', false) => { i += 1; "Gt" }
+                    ('<', true) => { i += 2; "Le" }
+                    ('<', false) => { i += 1; "Lt" }
+                    ('=', true) => { i += 2; "Eq" }
+                    ('!', true) => { i += 2; "Ne" }
+                    _ => return Err(format!("unexpected character '{c}' in condition: {source}")),
+                };
+                tokens.push(Token::Op(op));
+            }
+            _ if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let n = text.parse::<f64>().map_err(|e| format!("invalid number '{text}' in condition: {e}"))?;
+                tokens.push(Token::Number(n));
+            }
+            _ if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token::Ident(text));
+            }
+            _ => return Err(format!("unexpected character '{c}' in condition: {source}")),
+        }
+    }
+
+    Ok(tokens)
+}
+
+// --- Parser (recursive descent) ---
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), String> {
+        match self.advance() {
+            Some(tok) if &tok == expected => Ok(()),
+            other => Err(format!("expected {expected:?}, found {other:?}")),
+        }
+    }
+
+    fn parse_expression(&mut self) -> Result<Expr, String> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut node = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Ident(name)) if name == "or") {
+            self.advance();
+            let rhs = self.parse_and()?;
+            node = Expr::Logic { op: LogicOp::Or, lhs: Box::new(node), rhs: Some(Box::new(rhs)) };
+        }
+        Ok(node)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut node = self.parse_not()?;
+        while matches!(self.peek(), Some(Token::Ident(name)) if name == "and") {
+            self.advance();
+            let rhs = self.parse_not()?;
+            node = Expr::Logic { op: LogicOp::And, lhs: Box::new(node), rhs: Some(Box::new(rhs)) };
+        }
+        Ok(node)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, String> {
+        if matches!(self.peek(), Some(Token::Ident(name)) if name == "not") {
+            self.advance();
+            let operand = self.parse_not()?;
+            return Ok(Expr::Logic { op: LogicOp::Not, lhs: Box::new(operand), rhs: None });
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, String> {
+        let lhs = self.parse_primary()?;
+        let op = match self.peek() {
+            Some(Token::Op("Gt")) => Some(CompareOp::Gt),
+            Some(Token::Op("Lt")) => Some(CompareOp::Lt),
+            Some(Token::Op("Ge")) => Some(CompareOp::Ge),
+            Some(Token::Op("Le")) => Some(CompareOp::Le),
+            Some(Token::Op("Eq")) => Some(CompareOp::Eq),
+            Some(Token::Op("Ne")) => Some(CompareOp::Ne),
+            _ => None,
+        };
+        let Some(op) = op else { return Ok(lhs) };
+        self.advance();
+        let rhs = self.parse_primary()?;
+        Ok(Expr::Compare { op, lhs: Box::new(lhs), rhs: Box::new(rhs) })
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        match self.advance() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(Literal::Number(n))),
+            Some(Token::Str(s)) => Ok(Expr::Literal(Literal::Str(s))),
+            Some(Token::LParen) => {
+                let expr = self.parse_expression()?;
+                self.expect(&Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) if name == "true" => Ok(Expr::Literal(Literal::Bool(true))),
+            Some(Token::Ident(name)) if name == "false" => Ok(Expr::Literal(Literal::Bool(false))),
+            Some(Token::Ident(name)) if matches!(self.peek(), Some(Token::LParen)) => {
+                self.advance(); // consume '('
+                let mut args = Vec::new();
+                if !matches!(self.peek(), Some(Token::RParen)) {
+                    args.push(self.parse_expression()?);
+                    while matches!(self.peek(), Some(Token::Comma)) {
+                        self.advance();
+                        args.push(self.parse_expression()?);
+                    }
+                }
+                self.expect(&Token::RParen)?;
+                Ok(Expr::Call { name, args })
+            }
+            Some(Token::Ident(name)) => Ok(Expr::Field(name.split('.').map(str::to_string).collect())),
+            other => Err(format!("unexpected token while parsing condition: {other:?}")),
+        }
+    }
+}
+
+fn parse(source: &str) -> Result<Expr, String> {
+    let tokens = lex(source)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_expression()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(format!("unexpected trailing tokens in condition: {source}"));
+    }
+    Ok(expr)
+}
+
+// --- Evaluator ---
+
+fn resolve_field(path: &[String], ctx: &serde_json::Value) -> serde_json::Value {
+    let mut current = ctx;
+    for segment in path {
+        match current.get(segment) {
+            Some(v) => current = v,
+            None => return serde_json::Value::Null,
+        }
+    }
+    current.clone()
+}
+
+fn as_f64(value: &serde_json::Value) -> Result<f64, String> {
+    value.as_f64().ok_or_else(|| format!("expected a number, found {value}"))
+}
+
+fn as_str(value: &serde_json::Value) -> Result<&str, String> {
+    value.as_str().ok_or_else(|| format!("expected a string, found {value}"))
+}
+
+fn as_bool(value: &serde_json::Value) -> Result<bool, String> {
+    value.as_bool().ok_or_else(|| format!("expected a bool, found {value}"))
+}
+
+fn eval(expr: &Expr, ctx: &serde_json::Value) -> Result<serde_json::Value, String> {
+    match expr {
+        Expr::Literal(Literal::Number(n)) => Ok(serde_json::json!(n)),
+        Expr::Literal(Literal::Str(s)) => Ok(serde_json::json!(s)),
+        Expr::Literal(Literal::Bool(b)) => Ok(serde_json::json!(b)),
+        Expr::Field(path) => Ok(resolve_field(path, ctx)),
+        Expr::Compare { op, lhs, rhs } => {
+            let lhs = eval(lhs, ctx)?;
+            let rhs = eval(rhs, ctx)?;
+            let result = match op {
+                CompareOp::Eq => lhs == rhs,
+                CompareOp::Ne => lhs != rhs,
+                CompareOp::Gt => as_f64(&lhs)? > as_f64(&rhs)?,
+                CompareOp::Lt => as_f64(&lhs)? < as_f64(&rhs)?,
+                CompareOp::Ge => as_f64(&lhs)? >= as_f64(&rhs)?,
+                CompareOp::Le => as_f64(&lhs)? <= as_f64(&rhs)?,
+            };
+            Ok(serde_json::json!(result))
+        }
+        Expr::Logic { op: LogicOp::Not, lhs, .. } => {
+            let value = as_bool(&eval(lhs, ctx)?)?;
+            Ok(serde_json::json!(!value))
+        }
+        Expr::Logic { op, lhs, rhs } => {
+            let Some(rhs) = rhs else { return Err("logic operator is missing its right-hand side".to_string()) };
+            let lhs = as_bool(&eval(lhs, ctx)?)?;
+            let result = match op {
+                LogicOp::And => lhs && as_bool(&eval(rhs, ctx)?)?,
+                LogicOp::Or => lhs || as_bool(&eval(rhs, ctx)?)?,
+                LogicOp::Not => unreachable!("Not is handled above"),
+            };
+            Ok(serde_json::json!(result))
+        }
+        Expr::Call { name, args } => eval_call(name, args, ctx),
+    }
+}
+
+fn eval_call(name: &str, args: &[Expr], ctx: &serde_json::Value) -> Result<serde_json::Value, String> {
+    match name {
+        "lower" => {
+            let [arg] = args else { return Err(format!("lower() expects 1 argument, got {}", args.len())) };
+            let value = eval(arg, ctx)?;
+            Ok(serde_json::json!(as_str(&value)?.to_lowercase()))
+        }
+        "contains" => {
+            let [list, needle] = args else { return Err(format!("contains() expects 2 arguments, got {}", args.len())) };
+            let list = eval(list, ctx)?;
+            let needle = eval(needle, ctx)?;
+            let items = list.as_array().ok_or_else(|| format!("contains() expects an array, found {list}"))?;
+            Ok(serde_json::json!(items.contains(&needle)))
+        }
+        other => Err(format!("unknown function '{other}' in condition")),
+    }
+}